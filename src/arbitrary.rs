@@ -0,0 +1,43 @@
+use std::hash::{BuildHasher, Hash};
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::{KeySet, Map};
+
+impl<'a, K, O, V, S, G: KeySet<K, S>> Arbitrary<'a> for Map<K, O, V, S, G>
+where
+    K: Arbitrary<'a> + Clone + Eq + Hash,
+    O: Arbitrary<'a> + Clone + Ord,
+    V: Arbitrary<'a>,
+    S: BuildHasher + Clone + Default,
+{
+    /// Later generated entries overwrite earlier ones with the same keys. To get more entries
+    /// sharing an order, use an order type with few values.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter::<(K, O, V)>()?.collect()
+    }
+
+    fn arbitrary_take_rest(u: Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_take_rest_iter::<(K, O, V)>()?.collect()
+    }
+
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use crate::Map;
+
+    #[test]
+    fn arbitrary() {
+        let bytes: Vec<u8> = (0..=255).rev().collect();
+        let map = Map::<u8, bool, u8>::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        assert!(!map.is_empty());
+        assert!(map.ordered_keys.len() <= 2);
+        assert_eq!(map.keys().count(), map.len());
+    }
+}
@@ -0,0 +1,107 @@
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+
+use borsh::io::{Error, ErrorKind, Read, Result, Write};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use crate::{KeySet, Map};
+
+impl<K, O, V, S, G: KeySet<K, S>> BorshSerialize for Map<K, O, V, S, G>
+where
+    K: BorshSerialize + Ord + Hash,
+    O: BorshSerialize,
+    V: BorshSerialize,
+    S: BuildHasher,
+{
+    /// Serializes the number of entries as a `u32`, followed by `(key, order, value)` tuples
+    /// sorted by order and then by key, so equal maps always produce the same bytes.
+    fn serialize<W: Write>(&self, writer: &mut W) -> Result<()> {
+        u32::try_from(self.len())
+            .map_err(|_| ErrorKind::InvalidData)?
+            .serialize(writer)?;
+        for (order, keys) in &self.ordered_keys {
+            let mut keys: Vec<&K> = keys.iter().collect();
+            keys.sort_unstable();
+            for key in keys {
+                key.serialize(writer)?;
+                order.serialize(writer)?;
+                self[key].serialize(writer)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<K, O, V, S, G: KeySet<K, S>> BorshDeserialize for Map<K, O, V, S, G>
+where
+    K: BorshDeserialize + Clone + Ord + Hash,
+    O: BorshDeserialize + Clone + Ord,
+    V: BorshDeserialize,
+    S: BuildHasher + Clone + Default,
+{
+    /// Accepts only the layout produced by serialization: the tuples must be strictly ascending
+    /// by order and then by key, and the keys must be unique.
+    fn deserialize_reader<R: Read>(reader: &mut R) -> Result<Self> {
+        let len = u32::deserialize_reader(reader)?;
+        let mut map = Map::default();
+        let mut last: Option<(O, K)> = None;
+        for _ in 0..len {
+            let key = K::deserialize_reader(reader)?;
+            let order = O::deserialize_reader(reader)?;
+            let value = V::deserialize_reader(reader)?;
+            if let Some((last_order, last_key)) = &last {
+                if (last_order, last_key) >= (&order, &key) {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "entries were not serialized in ascending order",
+                    ));
+                }
+            }
+            if map.contains_key(&key) {
+                return Err(Error::new(ErrorKind::InvalidData, "duplicate key"));
+            }
+            last = Some((order.clone(), key.clone()));
+            map.add(key, order, value);
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Map;
+
+    #[test]
+    fn round_trip() {
+        let mut map = Map::new();
+        for i in 0..100u32 {
+            map.add(i, i % 3, i * 2);
+        }
+        let bytes = borsh::to_vec(&map).unwrap();
+        assert_eq!(bytes.len(), 4 + 100 * 12);
+        assert_eq!(
+            &bytes[..16],
+            &[100, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(borsh::to_vec(&map.clone()).unwrap(), bytes);
+        assert_eq!(
+            borsh::from_slice::<Map<u32, u32, u32>>(&bytes).unwrap(),
+            map
+        );
+    }
+
+    #[test]
+    fn non_canonical_input() {
+        let unsorted = borsh::to_vec(&vec![(2u8, 0u8, 0u8), (1, 0, 0)]).unwrap();
+        assert!(borsh::from_slice::<Map<u8, u8, u8>>(&unsorted).is_err());
+
+        let duplicate = borsh::to_vec(&vec![(1u8, 0u8, 0u8), (1, 1, 0)]).unwrap();
+        assert!(borsh::from_slice::<Map<u8, u8, u8>>(&duplicate).is_err());
+
+        let sorted = borsh::to_vec(&vec![(2u8, 0u8, 0u8), (1, 1, 0)]).unwrap();
+        assert_eq!(
+            borsh::from_slice::<Map<u8, u8, u8>>(&sorted).unwrap(),
+            Map::from([(2, 0, 0), (1, 1, 0)])
+        );
+    }
+}
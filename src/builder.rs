@@ -0,0 +1,122 @@
+use core::hash::{BuildHasher, Hash};
+
+#[cfg(feature = "heapless")]
+use crate::FixedMap;
+use crate::{
+    DefaultHashBuilder, DuplicatePolicy, KeySet, Map, OccupiedError, TieBreak, Unspecified,
+};
+
+/// A configuration for creating a [`Map`](crate::Map).
+///
+/// Constructed by [`MapBuilder::new`].
+#[derive(Clone, Debug)]
+pub struct MapBuilder<S = DefaultHashBuilder, T = Unspecified, P = ()> {
+    capacity: usize,
+    hasher: S,
+    tie_break: T,
+    duplicate_policy: P,
+}
+
+impl MapBuilder {
+    /// Creates a builder for an empty map with the default hasher, no preallocated capacity and
+    /// entries with equal orders in no particular order.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a map bounded to `N` entries, which never allocates. The bounded map keeps
+    /// entries with equal orders in insertion order and finds keys by scanning, so it is only
+    /// available before a hasher, a tie-breaking policy or a duplicate policy is set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the builder's capacity is larger than `N`.
+    #[cfg(feature = "heapless")]
+    pub fn build_bounded<K, O, V, const N: usize>(self) -> FixedMap<K, O, V, N> {
+        assert!(self.capacity <= N, "capacity exceeds the bound of the map");
+        FixedMap::new()
+    }
+}
+
+impl<S: Default, T: Default> Default for MapBuilder<S, T> {
+    /// Creates a builder for an empty map with the default hasher, no preallocated capacity and
+    /// the default tie-breaking policy.
+    fn default() -> Self {
+        Self {
+            capacity: 0,
+            hasher: S::default(),
+            tie_break: T::default(),
+            duplicate_policy: (),
+        }
+    }
+}
+
+impl<S, T, P> MapBuilder<S, T, P> {
+    /// Sets the number of entries the map can hold without reallocating the value map.
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Sets the hasher of the map.
+    pub fn hasher<S2>(self, hasher: S2) -> MapBuilder<S2, T, P> {
+        MapBuilder {
+            capacity: self.capacity,
+            hasher,
+            tie_break: self.tie_break,
+            duplicate_policy: self.duplicate_policy,
+        }
+    }
+
+    /// Sets the order in which the map serves and visits entries with equal orders.
+    pub fn tie_break<T2: TieBreak>(self, tie_break: T2) -> MapBuilder<S, T2, P> {
+        MapBuilder {
+            capacity: self.capacity,
+            hasher: self.hasher,
+            tie_break,
+            duplicate_policy: self.duplicate_policy,
+        }
+    }
+
+    /// Sets how [`MapBuilder::build_from`] resolves entries with the same keys.
+    pub fn duplicate_policy<'a, K, O, V>(
+        self,
+        duplicate_policy: DuplicatePolicy<'a, K, O, V>,
+    ) -> MapBuilder<S, T, DuplicatePolicy<'a, K, O, V>> {
+        MapBuilder {
+            capacity: self.capacity,
+            hasher: self.hasher,
+            tie_break: self.tie_break,
+            duplicate_policy,
+        }
+    }
+
+    /// Creates the map.
+    pub fn build<K, O, V>(self) -> Map<K, O, V, S, T::Group<K, S>>
+    where
+        T: TieBreak,
+    {
+        Map::with_capacity_and_hasher_in(self.capacity, self.hasher)
+    }
+}
+
+impl<'a, S, T, K, O, V> MapBuilder<S, T, DuplicatePolicy<'a, K, O, V>>
+where
+    S: BuildHasher + Clone,
+    T: TieBreak,
+    T::Group<K, S>: KeySet<K, S>,
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+{
+    /// Creates the map and adds the entries with [`Map::extend_with_policy`] using the duplicate
+    /// policy. Returns the entry rejected by [`DuplicatePolicy::Error`] in the error.
+    #[allow(clippy::type_complexity)]
+    pub fn build_from<I: IntoIterator<Item = (K, O, V)>>(
+        self,
+        iter: I,
+    ) -> Result<Map<K, O, V, S, T::Group<K, S>>, OccupiedError<K, O, V>> {
+        let mut map = Map::with_capacity_and_hasher_in(self.capacity, self.hasher);
+        map.extend_with_policy(iter, self.duplicate_policy)?;
+        Ok(map)
+    }
+}
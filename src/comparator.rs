@@ -0,0 +1,194 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::hash::{BuildHasher, Hash};
+use core::ops::Bound;
+
+use hashbrown::HashSet;
+
+use crate::wrapped::{unwrap_entries, DrainRange, WrappedMap};
+use crate::{Compare, CompareBy, DefaultHashBuilder, GroupDrain, GroupIter, KeySet, Map};
+
+/// A map like [`Map`] with orders compared by the comparator `C` instead of their own [`Ord`]
+/// implementation.
+///
+/// The orders are stored wrapped in [`CompareBy`], so the map order is ascending by the
+/// comparator, and "smallest" and "largest" mean first and last by it. Comparators are
+/// stateless, so lookups wrap borrowed orders without cloning them.
+pub type ComparatorMap<K, O, V, C, S = DefaultHashBuilder, G = HashSet<K, S>> =
+    WrappedMap<K, O, V, CompareBy<O, C>, S, G>;
+
+impl<K, O, V> Map<K, O, V> {
+    /// Creates an empty map with orders compared by `C`. The comparator only picks the type, since
+    /// comparators are stateless.
+    pub fn with_comparator<C: Compare<O> + Default>(_comparator: C) -> ComparatorMap<K, O, V, C> {
+        ComparatorMap::new()
+    }
+}
+
+impl<K, O, V, C, S, G> ComparatorMap<K, O, V, C, S, G>
+where
+    K: Clone + Eq + Hash,
+    O: Clone,
+    C: Compare<O> + Default,
+    S: BuildHasher + Clone,
+    G: KeySet<K, S>,
+{
+    /// Returns the smallest order, without looking at the entries that have it.
+    pub fn min_order(&self) -> Option<&O> {
+        Some(&self.map.min_order()?.order)
+    }
+
+    /// Returns the largest order, without looking at the entries that have it.
+    pub fn max_order(&self) -> Option<&O> {
+        Some(&self.map.max_order()?.order)
+    }
+
+    /// Returns `true` if the entry with the key has the smallest order, so it would be removed by
+    /// the next [`ComparatorMap::remove_smallest`].
+    pub fn is_smallest<Q: Eq + Hash + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map.is_smallest(key)
+    }
+
+    /// Compares the orders of two entries. Returns `None` if either key has no entry.
+    pub fn cmp_keys<Q: Eq + Hash + ?Sized>(&self, a: &Q, b: &Q) -> Option<Ordering>
+    where
+        K: Borrow<Q>,
+    {
+        self.map.cmp_keys(a, b)
+    }
+
+    /// Returns the number of entries with orders less than `order`.
+    pub fn count_below(&self, order: &O) -> usize {
+        self.map.count_below(CompareBy::from_ref(order))
+    }
+
+    /// Removes the entries with orders less than or equal to `order`, returning them as an
+    /// iterator ascending by order.
+    pub fn drain_up_to(&mut self, order: &O) -> DrainRange<'_, K, O, V, CompareBy<O, C>, S> {
+        self.drain_range((Bound::Unbounded, Bound::Included(order)))
+    }
+
+    /// Removes the entries with orders less than `order`, returning them as an iterator ascending
+    /// by order.
+    pub fn drain_below(&mut self, order: &O) -> DrainRange<'_, K, O, V, CompareBy<O, C>, S> {
+        self.drain_range((Bound::Unbounded, Bound::Excluded(order)))
+    }
+
+    /// Consumes the map, returning a map with the orders replaced by `f` of the key and the order.
+    /// The returned map orders them by their own [`Ord`] implementation.
+    pub fn map_orders<O2: Clone + Ord, F: FnMut(&K, O) -> O2>(
+        self,
+        mut f: F,
+    ) -> Map<K, O2, V, S, G> {
+        self.map.map_orders(|key, order| f(key, order.order))
+    }
+
+    /// Returns the `n`-th smallest distinct order, counting from zero.
+    pub fn nth_smallest_order(&self, n: usize) -> Option<&O> {
+        Some(&self.map.nth_smallest_order(n)?.order)
+    }
+
+    /// Returns the `n`-th entry ascending by order, counting from zero.
+    pub fn nth_smallest_entry(&self, n: usize) -> Option<(&K, &O, &V)> {
+        let (key, order, value) = self.map.nth_smallest_entry(n)?;
+        Some((key, &order.order, value))
+    }
+
+    /// Removes entries with the smallest order value. Items in the result are not ordered.
+    pub fn remove_smallest(&mut self) -> Option<(O, Vec<(K, V)>)> {
+        let (order, group) = self.map.remove_smallest()?;
+        Some((order.order, group))
+    }
+
+    /// Removes a single entry with the smallest order value, the one of its group which is served
+    /// first.
+    pub fn pop_one_smallest(&mut self) -> Option<(K, O, V)> {
+        let (key, order, value) = self.map.pop_one_smallest()?;
+        Some((key, order.order, value))
+    }
+
+    /// Removes up to `n` entries with the smallest orders, returning them ascending by order.
+    pub fn remove_smallest_n(&mut self, n: usize) -> Vec<(K, O, V)> {
+        unwrap_entries(self.map.remove_smallest_n(n))
+    }
+
+    /// Removes entries ascending by order for as long as `pred` returns `true`, stopping at the
+    /// first entry for which it returns `false`, which is kept. Returns the removed entries.
+    pub fn remove_smallest_while<F: FnMut(&K, &O, &V) -> bool>(
+        &mut self,
+        mut pred: F,
+    ) -> Vec<(K, O, V)> {
+        unwrap_entries(
+            self.map
+                .remove_smallest_while(|key, order, value| pred(key, &order.order, value)),
+        )
+    }
+
+    /// Removes entries with the smallest order value, appending them to `group` in no particular
+    /// order. Returns the order, or `None` if the map is empty.
+    pub fn remove_smallest_into(&mut self, group: &mut Vec<(K, V)>) -> Option<O> {
+        Some(self.map.remove_smallest_into(group)?.order)
+    }
+
+    /// Appends references to entries with the smallest order value to `group` in no particular
+    /// order. Returns the order, or `None` if the map is empty.
+    pub fn peek_smallest_into<'a>(&'a self, group: &mut Vec<(&'a K, &'a V)>) -> Option<&'a O> {
+        Some(&self.map.peek_smallest_into(group)?.order)
+    }
+
+    /// Returns references to entries with the smallest order value. The references are unordered.
+    pub fn peek_smallest(&self) -> Option<(&O, Vec<(&K, &V)>)> {
+        let (order, group) = self.map.peek_smallest()?;
+        Some((&order.order, group))
+    }
+
+    /// Returns the smallest order value and an iterator over the keys and values of its entries,
+    /// without allocating. The entries are not ordered.
+    #[allow(clippy::type_complexity)]
+    pub fn peek_smallest_iter(&self) -> Option<(&O, GroupIter<'_, K, CompareBy<O, C>, V, S, G>)> {
+        let (order, group) = self.map.peek_smallest_iter()?;
+        Some((&order.order, group))
+    }
+
+    /// Removes the group with the smallest order value, returning the order and an iterator over
+    /// the entries of the group. The entries are not ordered.
+    #[allow(clippy::type_complexity)]
+    pub fn drain_smallest(&mut self) -> Option<(O, GroupDrain<'_, K, CompareBy<O, C>, V, S, G>)> {
+        let (order, group) = self.map.drain_smallest()?;
+        Some((order.order, group))
+    }
+
+    /// Removes entries with the largest order value. Items in the result are not ordered.
+    pub fn remove_largest(&mut self) -> Option<(O, Vec<(K, V)>)> {
+        let (order, group) = self.map.remove_largest()?;
+        Some((order.order, group))
+    }
+
+    /// Returns references to entries with the largest order value. The references are unordered.
+    pub fn peek_largest(&self) -> Option<(&O, Vec<(&K, &V)>)> {
+        let (order, group) = self.map.peek_largest()?;
+        Some((&order.order, group))
+    }
+
+    /// Moves an entry to a new order if it is smaller than the current one. Returns `true` if the
+    /// order was changed.
+    pub fn change_order_if_smaller<Q: Eq + Hash + ?Sized>(&mut self, key: &Q, order: O) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map.change_order_if_smaller(key, CompareBy::new(order))
+    }
+
+    /// Moves an entry to a new order if it is larger than the current one. Returns `true` if the
+    /// order was changed.
+    pub fn change_order_if_larger<Q: Eq + Hash + ?Sized>(&mut self, key: &Q, order: O) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map.change_order_if_larger(key, CompareBy::new(order))
+    }
+}
@@ -0,0 +1,165 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::hash::{BuildHasher, Hash};
+use core::ops::Bound;
+
+use crate::{remove_ordered_key, DefaultHashBuilder, GroupIter, HashSet, KeySet, Map};
+
+fn next_order<'a, O: Ord, G>(ordered_keys: &'a BTreeMap<O, G>, order: Option<&O>) -> Option<&'a O> {
+    let start = order.map_or(Bound::Unbounded, Bound::Excluded);
+    let (order, _keys) = ordered_keys.range((start, Bound::Unbounded)).next()?;
+    Some(order)
+}
+
+fn prev_order<'a, O: Ord, G>(ordered_keys: &'a BTreeMap<O, G>, order: Option<&O>) -> Option<&'a O> {
+    let end = order.map_or(Bound::Unbounded, Bound::Excluded);
+    let (order, _keys) = ordered_keys.range((Bound::Unbounded, end)).next_back()?;
+    Some(order)
+}
+
+/// A cursor over the order groups of a [`Map`](crate::Map). It points either at a group or at a
+/// ghost position past both ends; moving past an end leads to the ghost, and moving from the ghost
+/// leads to the group at the other end.
+///
+/// Constructed by [`Map::lower_bound`](crate::Map::lower_bound) and
+/// [`Map::upper_bound`](crate::Map::upper_bound).
+pub struct Cursor<'a, K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    pub(crate) map: &'a Map<K, O, V, S, G>,
+    pub(crate) order: Option<&'a O>,
+}
+
+impl<K, O, V, S, G> Clone for Cursor<'_, K, O, V, S, G> {
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map,
+            order: self.order,
+        }
+    }
+}
+
+impl<'a, K: Eq + Hash, O: Ord, V, S: BuildHasher, G: KeySet<K, S>> Cursor<'a, K, O, V, S, G> {
+    /// Returns the order of the current group, or `None` at the ghost position.
+    pub fn order(&self) -> Option<&'a O> {
+        self.order
+    }
+
+    /// Returns an iterator over the keys and values of the current group, or `None` at the ghost
+    /// position. The entries are not ordered.
+    pub fn group(&self) -> Option<GroupIter<'a, K, O, V, S, G>> {
+        let keys = self.map.ordered_keys.get(self.order?)?;
+        Some(GroupIter {
+            keys: keys.iter(),
+            values: &self.map.values,
+        })
+    }
+
+    /// Moves to the group with the next larger order.
+    pub fn move_next(&mut self) {
+        self.order = next_order(&self.map.ordered_keys, self.order);
+    }
+
+    /// Moves to the group with the next smaller order.
+    pub fn move_prev(&mut self) {
+        self.order = prev_order(&self.map.ordered_keys, self.order);
+    }
+
+    /// Returns the order of the group [`Cursor::move_next`] would move to.
+    pub fn peek_next(&self) -> Option<&'a O> {
+        next_order(&self.map.ordered_keys, self.order)
+    }
+
+    /// Returns the order of the group [`Cursor::move_prev`] would move to.
+    pub fn peek_prev(&self) -> Option<&'a O> {
+        prev_order(&self.map.ordered_keys, self.order)
+    }
+}
+
+/// A cursor over the order groups of a [`Map`](crate::Map) which can also edit the map. It moves
+/// like [`Cursor`]. If the current group loses all of its entries, the cursor keeps its position
+/// between the neighbouring groups.
+///
+/// Constructed by [`Map::lower_bound_mut`](crate::Map::lower_bound_mut) and
+/// [`Map::upper_bound_mut`](crate::Map::upper_bound_mut).
+pub struct CursorMut<'a, K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    pub(crate) map: &'a mut Map<K, O, V, S, G>,
+    pub(crate) order: Option<O>,
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>>
+    CursorMut<'_, K, O, V, S, G>
+{
+    /// Returns the order of the current group, or `None` at the ghost position.
+    pub fn order(&self) -> Option<&O> {
+        self.order.as_ref()
+    }
+
+    /// Returns an iterator over the keys and values of the current group, or `None` at the ghost
+    /// position or if the group has no entries. The entries are not ordered.
+    pub fn group(&self) -> Option<GroupIter<'_, K, O, V, S, G>> {
+        self.as_cursor().group()
+    }
+
+    /// Returns a read-only cursor at the same position.
+    pub fn as_cursor(&self) -> Cursor<'_, K, O, V, S, G> {
+        Cursor {
+            map: self.map,
+            order: self.order.as_ref(),
+        }
+    }
+
+    /// Moves to the group with the next larger order.
+    pub fn move_next(&mut self) {
+        self.order = next_order(&self.map.ordered_keys, self.order.as_ref()).cloned();
+    }
+
+    /// Moves to the group with the next smaller order.
+    pub fn move_prev(&mut self) {
+        self.order = prev_order(&self.map.ordered_keys, self.order.as_ref()).cloned();
+    }
+
+    /// Returns the order of the group [`CursorMut::move_next`] would move to.
+    pub fn peek_next(&self) -> Option<&O> {
+        next_order(&self.map.ordered_keys, self.order.as_ref())
+    }
+
+    /// Returns the order of the group [`CursorMut::move_prev`] would move to.
+    pub fn peek_prev(&self) -> Option<&O> {
+        prev_order(&self.map.ordered_keys, self.order.as_ref())
+    }
+
+    /// Removes the current group and moves to the next one. Returns the order and the entries of
+    /// the removed group, or `None` at the ghost position. Items in the result are not ordered.
+    pub fn remove_current(&mut self) -> Option<(O, Vec<(K, V)>)> {
+        let order = self.order.take()?;
+        let keys = self.map.ordered_keys.remove(&order);
+        self.order = next_order(&self.map.ordered_keys, Some(&order)).cloned();
+        Some(self.map.remove_group(order, keys?))
+    }
+
+    /// Removes the entry with the key if it belongs to the current group.
+    pub fn remove_entry<Q: Eq + Hash + ?Sized>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let order = self.order.as_ref()?;
+        if self.map.get_order(key)? != order {
+            return None;
+        }
+        let (removed_key, (order, value)) = self.map.values.remove_entry(key).unwrap();
+        remove_ordered_key(&mut self.map.ordered_keys, &order, &removed_key);
+        Some((removed_key, value))
+    }
+
+    /// Adds an entry to the current group. Returns the old entry with the same key if there was
+    /// one.
+    ///
+    /// Panics if the cursor is at the ghost position.
+    pub fn add(&mut self, key: K, value: V) -> Option<(O, V)> {
+        let order = self
+            .order
+            .clone()
+            .expect("the cursor is at the ghost position");
+        self.map.add(key, order, value)
+    }
+}
@@ -0,0 +1,254 @@
+use alloc::collections::BTreeMap;
+use core::hash::{BuildHasher, Hash};
+
+use crate::{
+    add_ordered_key, hash_map, remove_ordered_key, DefaultHashBuilder, GroupDrain, GroupIter,
+    HashSet, KeySet, Map,
+};
+
+/// A view into a single entry of a [`Map`](crate::Map), which may either be vacant or occupied.
+///
+/// Constructed by [`Map::entry`](crate::Map::entry).
+pub enum Entry<'a, K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    Occupied(OccupiedEntry<'a, K, O, V, S, G>),
+    Vacant(VacantEntry<'a, K, O, V, S, G>),
+}
+
+/// A view into an occupied entry of a [`Map`](crate::Map).
+pub struct OccupiedEntry<'a, K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    pub(crate) entry: hash_map::OccupiedEntry<'a, K, (O, V), S>,
+    pub(crate) ordered_keys: &'a mut BTreeMap<O, G>,
+    pub(crate) hasher: S,
+}
+
+/// A view into a vacant entry of a [`Map`](crate::Map).
+pub struct VacantEntry<'a, K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    pub(crate) entry: hash_map::VacantEntry<'a, K, (O, V), S>,
+    pub(crate) ordered_keys: &'a mut BTreeMap<O, G>,
+    pub(crate) hasher: S,
+}
+
+impl<'a, K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>>
+    Entry<'a, K, O, V, S, G>
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts the order and the value if the entry is vacant. Returns references to the order
+    /// and the value of the entry.
+    pub fn or_insert(self, order: O, value: V) -> (&'a O, &'a mut V) {
+        self.or_insert_with(|| (order, value))
+    }
+
+    /// Inserts the order and the value returned by `f` if the entry is vacant. Returns references
+    /// to the order and the value of the entry.
+    pub fn or_insert_with<F: FnOnce() -> (O, V)>(self, f: F) -> (&'a O, &'a mut V) {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let (order, value) = f();
+                entry.insert(order, value)
+            }
+        }
+    }
+
+    /// Calls `f` on the value if the entry is occupied.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            let (_order, value) = entry.get_mut();
+            f(value);
+        }
+        self
+    }
+}
+
+impl<'a, K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>>
+    OccupiedEntry<'a, K, O, V, S, G>
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        self.entry.key()
+    }
+
+    /// Returns references to the order and the value of the entry.
+    pub fn get(&self) -> (&O, &V) {
+        let (order, value) = self.entry.get();
+        (order, value)
+    }
+
+    /// Returns a reference to the order and a mutable reference to the value of the entry.
+    pub fn get_mut(&mut self) -> (&O, &mut V) {
+        let (order, value) = self.entry.get_mut();
+        (order, value)
+    }
+
+    /// Converts the entry into references to its order and value, bound to the map's lifetime.
+    pub fn into_mut(self) -> (&'a O, &'a mut V) {
+        let (order, value) = self.entry.into_mut();
+        (order, value)
+    }
+
+    /// Moves the entry to a new order, returning the old one.
+    pub fn set_order(&mut self, order: O) -> O {
+        let (current_order, _value) = self.entry.get_mut();
+        if *current_order == order {
+            return order;
+        }
+        let old_order = core::mem::replace(current_order, order.clone());
+        remove_ordered_key(self.ordered_keys, &old_order, self.entry.key());
+        add_ordered_key(
+            self.ordered_keys,
+            &self.hasher,
+            order,
+            self.entry.key().clone(),
+        );
+        old_order
+    }
+
+    /// Removes the entry, returning its order and value.
+    pub fn remove(self) -> (O, V) {
+        let (_key, order, value) = self.remove_entry();
+        (order, value)
+    }
+
+    /// Removes the entry, returning its key, order and value.
+    pub fn remove_entry(self) -> (K, O, V) {
+        let (key, (order, value)) = self.entry.remove_entry();
+        remove_ordered_key(self.ordered_keys, &order, &key);
+        (key, order, value)
+    }
+}
+
+impl<'a, K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>>
+    VacantEntry<'a, K, O, V, S, G>
+{
+    /// Returns the key that would be used when inserting through the entry.
+    pub fn key(&self) -> &K {
+        self.entry.key()
+    }
+
+    /// Takes ownership of the key.
+    pub fn into_key(self) -> K {
+        self.entry.into_key()
+    }
+
+    /// Inserts the entry, returning references to its order and value.
+    pub fn insert(self, order: O, value: V) -> (&'a O, &'a mut V) {
+        add_ordered_key(
+            self.ordered_keys,
+            &self.hasher,
+            order.clone(),
+            self.entry.key().clone(),
+        );
+        let (order, value) = self.entry.insert((order, value));
+        (order, value)
+    }
+}
+
+/// A view into a single order group of a [`Map`](crate::Map), which may either be vacant or
+/// occupied.
+///
+/// Constructed by [`Map::order_entry`](crate::Map::order_entry).
+pub enum OrderEntry<'a, K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    Occupied(OccupiedOrderEntry<'a, K, O, V, S, G>),
+    Vacant(VacantOrderEntry<'a, K, O, V, S, G>),
+}
+
+/// A view into a non-empty order group of a [`Map`](crate::Map).
+pub struct OccupiedOrderEntry<'a, K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    pub(crate) map: &'a mut Map<K, O, V, S, G>,
+    pub(crate) order: O,
+}
+
+/// A view into an order that no entry of a [`Map`](crate::Map) has.
+pub struct VacantOrderEntry<'a, K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    pub(crate) map: &'a mut Map<K, O, V, S, G>,
+    pub(crate) order: O,
+}
+
+impl<K, O, V, S, G> OrderEntry<'_, K, O, V, S, G> {
+    /// Returns the order of the group.
+    pub fn order(&self) -> &O {
+        match self {
+            OrderEntry::Occupied(entry) => &entry.order,
+            OrderEntry::Vacant(entry) => &entry.order,
+        }
+    }
+}
+
+impl<'a, K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>>
+    OccupiedOrderEntry<'a, K, O, V, S, G>
+{
+    /// Returns the order of the group.
+    pub fn order(&self) -> &O {
+        &self.order
+    }
+
+    fn keys(&self) -> &G {
+        self.map.ordered_keys.get(&self.order).unwrap()
+    }
+
+    /// Returns the number of entries in the group.
+    #[allow(clippy::len_without_is_empty)] // An occupied group is never empty.
+    pub fn len(&self) -> usize {
+        self.keys().len()
+    }
+
+    /// Returns an iterator over the keys and values of the group. The entries are not ordered.
+    pub fn iter(&self) -> GroupIter<'_, K, O, V, S, G> {
+        GroupIter {
+            keys: self.keys().iter(),
+            values: &self.map.values,
+        }
+    }
+
+    /// Adds an entry to the group. Returns the old entry with the same key if there was one.
+    pub fn add(&mut self, key: K, value: V) -> Option<(O, V)> {
+        self.map.add(key, self.order.clone(), value)
+    }
+
+    /// Moves the whole group to a new order, merging it with the group already there. Returns the
+    /// old order; the entry refers to the merged group afterwards.
+    pub fn set_order(&mut self, order: O) -> O {
+        self.map.reassign_group(&self.order, order.clone());
+        core::mem::replace(&mut self.order, order)
+    }
+
+    /// Removes the group, returning an iterator over its keys and values. The entries are not
+    /// ordered.
+    pub fn drain(self) -> GroupDrain<'a, K, O, V, S, G> {
+        let keys = self.map.ordered_keys.remove(&self.order).unwrap();
+        GroupDrain::new(keys, &mut self.map.values)
+    }
+}
+
+impl<'a, K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>>
+    VacantOrderEntry<'a, K, O, V, S, G>
+{
+    /// Returns the order of the group.
+    pub fn order(&self) -> &O {
+        &self.order
+    }
+
+    /// Takes ownership of the order.
+    pub fn into_order(self) -> O {
+        self.order
+    }
+
+    /// Adds the first entry to the group. Returns the old entry with the same key if there was one
+    /// along with the now occupied group.
+    #[allow(clippy::type_complexity)]
+    pub fn add(self, key: K, value: V) -> (Option<(O, V)>, OccupiedOrderEntry<'a, K, O, V, S, G>) {
+        let old_entry = self.map.add(key, self.order.clone(), value);
+        let entry = OccupiedOrderEntry {
+            map: self.map,
+            order: self.order,
+        };
+        (old_entry, entry)
+    }
+}
@@ -0,0 +1,18 @@
+use core::fmt::{self, Debug, Display};
+
+/// The error returned by [`Map::try_add`](crate::Map::try_add) when the key already has an
+/// entry. Holds the entry that was not added.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OccupiedError<K, O, V> {
+    pub key: K,
+    pub order: O,
+    pub value: V,
+}
+
+impl<K, O, V> Display for OccupiedError<K, O, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("the key already has an entry")
+    }
+}
+
+impl<K: Debug, O: Debug, V: Debug> core::error::Error for OccupiedError<K, O, V> {}
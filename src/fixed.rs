@@ -0,0 +1,455 @@
+//! A map with statically bounded storage, for targets that can not allocate.
+
+use core::borrow::Borrow;
+use core::fmt::{self, Debug};
+use core::ops::{Bound, RangeBounds};
+
+use heapless::Vec;
+
+/// A map like [`Map`](crate::Map) which holds at most `N` entries inline and never allocates.
+///
+/// The entries are kept in an array sorted by order, so ordered reads are cheap, while key
+/// lookups scan all entries and insertion shifts the entries after the new one. This suits the
+/// small maps that fit in a fixed budget. Entries with equal orders are kept in insertion order.
+///
+/// Only the core of the [`Map`](crate::Map) API is provided: adding, looking up and removing
+/// entries, moving them to other orders, the priority queue operations on both ends, ranks and
+/// ordered iteration. The entry API, cursors, group iterators and the methods built on them are
+/// not, since they rely on the order index of `Map`.
+pub struct FixedMap<K, O, V, const N: usize> {
+    entries: Vec<(K, O, V), N>,
+}
+
+impl<K, O, V, const N: usize> FixedMap<K, O, V, N> {
+    /// Creates an empty map.
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the maximum number of entries, `N`.
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if no more entries with new keys can be added.
+    pub fn is_full(&self) -> bool {
+        self.entries.is_full()
+    }
+
+    /// Removes all entries.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Returns an iterator over the entries, ascending by order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &O, &V)> + ExactSizeIterator {
+        self.entries
+            .iter()
+            .map(|(key, order, value)| (key, order, value))
+    }
+
+    /// Returns an iterator over the keys, ascending by order.
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator {
+        self.entries.iter().map(|(key, _order, _value)| key)
+    }
+
+    /// Returns an iterator over the values, ascending by order.
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+        self.entries.iter().map(|(_key, _order, value)| value)
+    }
+
+    /// Returns the smallest order in the map.
+    pub fn min_order(&self) -> Option<&O> {
+        let (_key, order, _value) = self.entries.first()?;
+        Some(order)
+    }
+
+    /// Returns the largest order in the map.
+    pub fn max_order(&self) -> Option<&O> {
+        let (_key, order, _value) = self.entries.last()?;
+        Some(order)
+    }
+
+    /// Removes all entries, returning them as an iterator ascending by order.
+    pub fn drain(&mut self) -> IntoIter<K, O, V, N> {
+        IntoIter {
+            entries: core::mem::take(&mut self.entries).into_iter(),
+        }
+    }
+
+    /// Returns an iterator over the entries with mutable references to the values, ascending by
+    /// order.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl DoubleEndedIterator<Item = (&K, &O, &mut V)> + ExactSizeIterator {
+        self.entries
+            .iter_mut()
+            .map(|(key, order, value)| (&*key, &*order, value))
+    }
+}
+
+impl<K: Eq, O: Ord, V, const N: usize> FixedMap<K, O, V, N> {
+    fn position<Q: Eq + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        self.entries
+            .iter()
+            .position(|(k, _order, _value)| k.borrow() == key)
+    }
+
+    /// Returns `true` if the map contains an entry with the given key. Takes O(N) time.
+    pub fn contains_key<Q: Eq + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.position(key).is_some()
+    }
+
+    /// Returns references to the order and the value of an entry. Takes O(N) time.
+    pub fn get<Q: Eq + ?Sized>(&self, key: &Q) -> Option<(&O, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        let (_key, order, value) = &self.entries[self.position(key)?];
+        Some((order, value))
+    }
+
+    /// Returns a reference to the order and a mutable reference to the value of an entry. The order
+    /// can not be changed this way. Takes O(N) time.
+    pub fn get_mut<Q: Eq + ?Sized>(&mut self, key: &Q) -> Option<(&O, &mut V)>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.position(key)?;
+        let (_key, order, value) = &mut self.entries[index];
+        Some((order, value))
+    }
+
+    /// Returns a mutable reference to the value of an entry, leaving its order untouched.
+    pub fn get_value_mut<Q: Eq + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        let (_order, value) = self.get_mut(key)?;
+        Some(value)
+    }
+
+    /// Returns the old entry with the same key if there was one. If the map is full and has no
+    /// entry with the key, the entry is handed back as the error.
+    pub fn add(&mut self, key: K, order: O, value: V) -> Result<Option<(O, V)>, (K, O, V)> {
+        let old_entry = match self.position(&key) {
+            Some(index) => {
+                let (_key, old_order, old_value) = self.entries.remove(index);
+                Some((old_order, old_value))
+            }
+            None if self.is_full() => return Err((key, order, value)),
+            None => None,
+        };
+        let index = self
+            .entries
+            .partition_point(|(_key, entry_order, _value)| *entry_order <= order);
+        if self.entries.insert(index, (key, order, value)).is_err() {
+            unreachable!("a slot was checked or freed above");
+        }
+        Ok(old_entry)
+    }
+
+    /// Adds the entries in turn, replacing entries with the same keys. Stops at the first entry with
+    /// a new key which does not fit and hands it back as the error; the entries before it stay
+    /// added.
+    pub fn try_extend<I: IntoIterator<Item = (K, O, V)>>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), (K, O, V)> {
+        for (key, order, value) in iter {
+            self.add(key, order, value)?;
+        }
+        Ok(())
+    }
+
+    /// Returns a reference to the order of an entry. Takes O(N) time.
+    pub fn get_order<Q: Eq + ?Sized>(&self, key: &Q) -> Option<&O>
+    where
+        K: Borrow<Q>,
+    {
+        let (order, _value) = self.get(key)?;
+        Some(order)
+    }
+
+    /// Moves an entry to a new order, leaving its value untouched and putting it behind the
+    /// entries already there. Returns the old order, or `None` if there is no entry with the given
+    /// key.
+    pub fn set_order<Q: Eq + ?Sized>(&mut self, key: &Q, order: O) -> Option<O>
+    where
+        K: Borrow<Q>,
+    {
+        let index = self.position(key)?;
+        if self.entries[index].1 == order {
+            return Some(order);
+        }
+        let (key, old_order, value) = self.entries.remove(index);
+        let index = self
+            .entries
+            .partition_point(|(_key, entry_order, _value)| *entry_order <= order);
+        if self.entries.insert(index, (key, order, value)).is_err() {
+            unreachable!("the slot of the entry was freed above");
+        }
+        Some(old_order)
+    }
+
+    /// Returns the number of entries with orders smaller than the order of the entry with the key,
+    /// which is its position in a queue served by [`FixedMap::remove_smallest`]. Entries with
+    /// equal orders share the position.
+    pub fn rank<Q: Eq + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        let order = self.get_order(key)?;
+        Some(
+            self.entries
+                .partition_point(|(_key, entry_order, _value)| entry_order < order),
+        )
+    }
+
+    /// Removes an entry by key.
+    pub fn remove<Q: Eq + ?Sized>(&mut self, key: &Q) -> Option<(O, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let (_key, order, value) = self.entries.remove(self.position(key)?);
+        Some((order, value))
+    }
+
+    /// Keeps only the entries for which `f` returns `true`. The entries are visited ascending by
+    /// order.
+    pub fn retain<F: FnMut(&K, &O, &mut V) -> bool>(&mut self, mut f: F) {
+        self.entries
+            .retain_mut(|(key, order, value)| f(key, order, value));
+    }
+
+    /// Returns an iterator over the entries with orders in the range, ascending by order.
+    pub fn range<R: RangeBounds<O>>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&K, &O, &V)> + ExactSizeIterator {
+        let start =
+            self.entries
+                .partition_point(|(_key, order, _value)| match range.start_bound() {
+                    Bound::Included(start) => order < start,
+                    Bound::Excluded(start) => order <= start,
+                    Bound::Unbounded => false,
+                });
+        let end = self
+            .entries
+            .partition_point(|(_key, order, _value)| match range.end_bound() {
+                Bound::Included(end) => order <= end,
+                Bound::Excluded(end) => order < end,
+                Bound::Unbounded => true,
+            });
+        self.entries[start..end.max(start)]
+            .iter()
+            .map(|(key, order, value)| (key, order, value))
+    }
+
+    fn group_len(&self, order: &O) -> usize {
+        self.range(order..=order).len()
+    }
+
+    fn remove_group(&mut self, start: usize, len: usize) -> (O, Vec<(K, V), N>) {
+        let (key, order, value) = self.entries.remove(start);
+        let mut group = Vec::new();
+        let _ = group.push((key, value));
+        for _ in 1..len {
+            let (key, _order, value) = self.entries.remove(start);
+            let _ = group.push((key, value));
+        }
+        (order, group)
+    }
+
+    /// Removes entries with the smallest order value, in insertion order.
+    pub fn remove_smallest(&mut self) -> Option<(O, Vec<(K, V), N>)> {
+        let (_key, order, _value) = self.entries.first()?;
+        let len = self.group_len(order);
+        Some(self.remove_group(0, len))
+    }
+
+    /// Removes a single entry with the smallest order value, the one of its group which was added
+    /// first.
+    pub fn pop_one_smallest(&mut self) -> Option<(K, O, V)> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(self.entries.remove(0))
+    }
+
+    /// Returns references to entries with the smallest order value, in insertion order.
+    pub fn peek_smallest(&self) -> Option<(&O, Vec<(&K, &V), N>)> {
+        let (_key, order, _value) = self.entries.first()?;
+        let group = self
+            .range(order..=order)
+            .map(|(key, _order, value)| (key, value))
+            .collect();
+        Some((order, group))
+    }
+
+    /// Removes entries with the largest order value, in insertion order.
+    pub fn remove_largest(&mut self) -> Option<(O, Vec<(K, V), N>)> {
+        let (_key, order, _value) = self.entries.last()?;
+        let len = self.group_len(order);
+        Some(self.remove_group(self.len() - len, len))
+    }
+
+    /// Removes a single entry with the largest order value, the one of its group which was added
+    /// first.
+    pub fn pop_one_largest(&mut self) -> Option<(K, O, V)> {
+        let (_key, order, _value) = self.entries.last()?;
+        let len = self.group_len(order);
+        Some(self.entries.remove(self.len() - len))
+    }
+
+    /// Returns references to entries with the largest order value, in insertion order.
+    pub fn peek_largest(&self) -> Option<(&O, Vec<(&K, &V), N>)> {
+        let (_key, order, _value) = self.entries.last()?;
+        let group = self
+            .range(order..=order)
+            .map(|(key, _order, value)| (key, value))
+            .collect();
+        Some((order, group))
+    }
+}
+
+impl<K, O, V, const N: usize> Default for FixedMap<K, O, V, N> {
+    /// Creates an empty map.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, O, V, const N: usize> IntoIterator for FixedMap<K, O, V, N> {
+    type Item = (K, O, V);
+    type IntoIter = IntoIter<K, O, V, N>;
+
+    /// Returns an iterator over the entries, ascending by order.
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            entries: self.entries.into_iter(),
+        }
+    }
+}
+
+/// An owning iterator over the entries of a [`FixedMap`], ascending by order.
+///
+/// Constructed by [`FixedMap::into_iter`] and [`FixedMap::drain`].
+pub struct IntoIter<K, O, V, const N: usize> {
+    entries: <Vec<(K, O, V), N> as IntoIterator>::IntoIter,
+}
+
+impl<K, O, V, const N: usize> Iterator for IntoIter<K, O, V, N> {
+    type Item = (K, O, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<K: Clone, O: Clone, V: Clone, const N: usize> Clone for FixedMap<K, O, V, N> {
+    fn clone(&self) -> Self {
+        Self {
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<K: Debug, O: Debug, V: Debug, const N: usize> Debug for FixedMap<K, O, V, N> {
+    /// Formats the entries as a list of `(key, order, value)` tuples ascending by order.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixed_map() {
+        let mut map: FixedMap<u8, u8, &str, 3> = FixedMap::new();
+        assert_eq!(map.add(1, 20, "a"), Ok(None));
+        assert_eq!(map.add(2, 10, "b"), Ok(None));
+        assert_eq!(map.add(3, 20, "c"), Ok(None));
+        assert!(map.is_full());
+        assert_eq!(map.add(4, 0, "d"), Err((4, 0, "d")));
+        assert_eq!(map.add(2, 20, "e"), Ok(Some((10, "b"))));
+        assert_eq!(
+            map.iter().collect::<std::vec::Vec<_>>(),
+            [(&1, &20, &"a"), (&3, &20, &"c"), (&2, &20, &"e")]
+        );
+        assert_eq!(map.get(&3), Some((&20, &"c")));
+        assert_eq!(map.remove(&3), Some((20, "c")));
+        assert_eq!(map.add(5, 5, "f"), Ok(None));
+        assert_eq!(map.range(..20).count(), 1);
+
+        let (order, group) = map.peek_largest().unwrap();
+        assert_eq!(
+            (order, group.as_slice()),
+            (&20, &[(&1, &"a"), (&2, &"e")][..])
+        );
+        drop(group);
+        let (order, group) = map.remove_largest().unwrap();
+        assert_eq!((order, group.as_slice()), (20, &[(1, "a"), (2, "e")][..]));
+        assert_eq!(map.remove_smallest().map(|(order, _)| order), Some(5));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn fixed_map_queue() {
+        let mut map: FixedMap<u8, u8, &str, 4> = FixedMap::new();
+        assert_eq!(
+            map.try_extend([
+                (1, 20, "a"),
+                (2, 10, "b"),
+                (3, 20, "c"),
+                (4, 30, "d"),
+                (5, 0, "e")
+            ]),
+            Err((5, 0, "e"))
+        );
+        assert_eq!((map.min_order(), map.max_order()), (Some(&10), Some(&30)));
+        assert_eq!(map.rank(&3), Some(1));
+        assert_eq!(map.set_order(&2, 20), Some(10));
+        assert_eq!(map.keys().collect::<std::vec::Vec<_>>(), [&1, &3, &2, &4]);
+        assert_eq!(map.get_order(&2), Some(&20));
+        assert_eq!(map.set_order(&4, 0), Some(30));
+        assert_eq!(map.pop_one_smallest(), Some((4, 0, "d")));
+        assert_eq!(map.pop_one_largest(), Some((1, 20, "a")));
+        assert_eq!(
+            map.drain().collect::<std::vec::Vec<_>>(),
+            [(3, 20, "c"), (2, 20, "b")]
+        );
+        assert!(map.is_empty());
+        assert_eq!(map.pop_one_largest(), None);
+
+        map.add(6, 60, "f").unwrap();
+        assert_eq!(
+            map.into_iter().collect::<std::vec::Vec<_>>(),
+            [(6, 60, "f")]
+        );
+    }
+}
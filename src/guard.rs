@@ -0,0 +1,134 @@
+use core::hash::{BuildHasher, Hash};
+use core::ops::{Deref, DerefMut};
+
+use crate::{DefaultHashBuilder, HashSet, KeySet, Map};
+
+/// A guard giving mutable access to an entry with the smallest order of a [`Map`](crate::Map).
+/// The order can be changed through the guard; the entry is moved to its new group when the
+/// guard is dropped.
+///
+/// Constructed by [`Map::peek_smallest_mut`](crate::Map::peek_smallest_mut).
+pub struct PeekSmallestMut<
+    'a,
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    V,
+    S: BuildHasher + Clone = DefaultHashBuilder,
+    G: KeySet<K, S> = HashSet<K, S>,
+> {
+    pub(crate) map: &'a mut Map<K, O, V, S, G>,
+    pub(crate) key: K,
+    pub(crate) new_order: Option<O>,
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>>
+    PeekSmallestMut<'_, K, O, V, S, G>
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the order of the entry, including a change not yet applied.
+    pub fn order(&self) -> &O {
+        match &self.new_order {
+            Some(order) => order,
+            None => self.map.get_order(&self.key).unwrap(),
+        }
+    }
+
+    /// Sets a new order for the entry, applied when the guard is dropped.
+    pub fn set_order(&mut self, order: O) {
+        self.new_order = Some(order);
+    }
+
+    /// Removes the entry from the map, discarding a pending order change.
+    pub fn pop(mut this: Self) -> (K, O, V) {
+        this.new_order = None;
+        let key = this.key.clone();
+        let (order, value) = this.map.remove(&key).unwrap();
+        (key, order, value)
+    }
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>> Deref
+    for PeekSmallestMut<'_, K, O, V, S, G>
+{
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        let (_order, value) = self.map.values.get(&self.key).unwrap();
+        value
+    }
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>> DerefMut
+    for PeekSmallestMut<'_, K, O, V, S, G>
+{
+    fn deref_mut(&mut self) -> &mut V {
+        let (_order, value) = self.map.values.get_mut(&self.key).unwrap();
+        value
+    }
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>> Drop
+    for PeekSmallestMut<'_, K, O, V, S, G>
+{
+    fn drop(&mut self) {
+        if let Some(order) = self.new_order.take() {
+            self.map.set_order(&self.key, order);
+        }
+    }
+}
+
+/// A guard dereferencing to a copy of the order of an entry of a [`Map`](crate::Map). If the order
+/// was changed, the entry is moved to its new group when the guard is dropped.
+///
+/// Constructed by [`Map::order_mut`](crate::Map::order_mut).
+pub struct OrderMut<
+    'a,
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    V,
+    S: BuildHasher + Clone = DefaultHashBuilder,
+    G: KeySet<K, S> = HashSet<K, S>,
+> {
+    pub(crate) map: &'a mut Map<K, O, V, S, G>,
+    pub(crate) key: K,
+    pub(crate) order: O,
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>>
+    OrderMut<'_, K, O, V, S, G>
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>> Deref
+    for OrderMut<'_, K, O, V, S, G>
+{
+    type Target = O;
+
+    fn deref(&self) -> &O {
+        &self.order
+    }
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>> DerefMut
+    for OrderMut<'_, K, O, V, S, G>
+{
+    fn deref_mut(&mut self) -> &mut O {
+        &mut self.order
+    }
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>> Drop
+    for OrderMut<'_, K, O, V, S, G>
+{
+    fn drop(&mut self) {
+        self.map.set_order(&self.key, self.order.clone());
+    }
+}
@@ -0,0 +1,1161 @@
+use alloc::collections::{btree_map, BTreeMap};
+use alloc::vec::{self, Vec};
+use core::hash::{BuildHasher, Hash};
+use core::iter::{self, FusedIterator};
+use core::marker::PhantomData;
+use core::ops::Bound;
+use core::ptr::NonNull;
+
+use crate::{hash_map, DefaultHashBuilder, HashMap, HashSet, KeySet, Map};
+
+/// Flattens the key iterators of the groups of the order index into a stream of keys. When the
+/// groups are exhausted from one end, the remaining keys of the group at the other end are taken.
+pub(crate) struct Flatten<T, I> {
+    groups: T,
+    front: Option<I>,
+    back: Option<I>,
+    len: usize,
+}
+
+impl<T: Clone, I: Clone> Clone for Flatten<T, I> {
+    fn clone(&self) -> Self {
+        Self {
+            groups: self.groups.clone(),
+            front: self.front.clone(),
+            back: self.back.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<T, I> Flatten<T, I> {
+    pub(crate) fn new(groups: T, len: usize) -> Self {
+        Self {
+            groups,
+            front: None,
+            back: None,
+            len,
+        }
+    }
+}
+
+impl<T: Iterator<Item = I>, I: Iterator> Iterator for Flatten<T, I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let key = loop {
+            if let Some(key) = self.front.as_mut().and_then(Iterator::next) {
+                break key;
+            }
+            match self.groups.next() {
+                Some(keys) => self.front = Some(keys),
+                None => break self.back.as_mut()?.next()?,
+            }
+        };
+        self.len -= 1;
+        Some(key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T: DoubleEndedIterator<Item = I>, I: DoubleEndedIterator> DoubleEndedIterator
+    for Flatten<T, I>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.len == 0 {
+            return None;
+        }
+        let key = loop {
+            if let Some(key) = self.back.as_mut().and_then(DoubleEndedIterator::next_back) {
+                break key;
+            }
+            match self.groups.next_back() {
+                Some(keys) => self.back = Some(keys),
+                None => break self.front.as_mut()?.next_back()?,
+            }
+        };
+        self.len -= 1;
+        Some(key)
+    }
+}
+
+type KeysOf<'a, K, S, G> = <G as KeySet<K, S>>::Iter<'a>;
+
+type GroupKeys<'a, K, O, S, G> = Flatten<
+    iter::Map<btree_map::Values<'a, O, G>, fn(&'a G) -> KeysOf<'a, K, S, G>>,
+    KeysOf<'a, K, S, G>,
+>;
+
+fn group_keys<'a, K, O, S, G: KeySet<K, S>>(
+    groups: btree_map::Values<'a, O, G>,
+    len: usize,
+) -> GroupKeys<'a, K, O, S, G> {
+    Flatten::new(groups.map(G::iter as fn(&'a G) -> _), len)
+}
+
+type IntoGroupKeys<K, O, S, G> = Flatten<
+    iter::Map<btree_map::IntoValues<O, G>, fn(G) -> <G as KeySet<K, S>>::IntoKeys>,
+    <G as KeySet<K, S>>::IntoKeys,
+>;
+
+fn into_group_keys<K, O, S, G: KeySet<K, S>>(
+    groups: btree_map::IntoValues<O, G>,
+    len: usize,
+) -> IntoGroupKeys<K, O, S, G> {
+    Flatten::new(groups.map(G::into_keys as fn(G) -> _), len)
+}
+
+/// An iterator over the entries of a [`Map`](crate::Map), ascending by order.
+pub struct Iter<'a, K, O, V, S = DefaultHashBuilder, G: KeySet<K, S> = HashSet<K, S>>
+where
+    K: 'a,
+    G: 'a,
+{
+    keys: GroupKeys<'a, K, O, S, G>,
+    values: &'a HashMap<K, (O, V), S>,
+}
+
+impl<K, O, V, S, G: KeySet<K, S>> Clone for Iter<'_, K, O, V, S, G> {
+    fn clone(&self) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            values: self.values,
+        }
+    }
+}
+
+impl<'a, K, O, V, S, G: KeySet<K, S>> Iter<'a, K, O, V, S, G> {
+    pub(crate) fn new(
+        groups: btree_map::Values<'a, O, G>,
+        values: &'a HashMap<K, (O, V), S>,
+    ) -> Self {
+        Self {
+            keys: group_keys(groups, values.len()),
+            values,
+        }
+    }
+
+    fn entry(&self, key: &'a K) -> (&'a K, &'a O, &'a V)
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+    {
+        let (order, value) = self.values.get(key).unwrap();
+        (key, order, value)
+    }
+}
+
+impl<'a, K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> Iterator for Iter<'a, K, O, V, S, G> {
+    type Item = (&'a K, &'a O, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        Some(self.entry(key))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> DoubleEndedIterator
+    for Iter<'_, K, O, V, S, G>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next_back()?;
+        Some(self.entry(key))
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> ExactSizeIterator
+    for Iter<'_, K, O, V, S, G>
+{
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> FusedIterator
+    for Iter<'_, K, O, V, S, G>
+{
+}
+
+/// Hands out mutable references to the values of a map for its whole borrow, one key at a time.
+pub(crate) struct ValuesMut<'a, K, O, V, S> {
+    values: NonNull<HashMap<K, (O, V), S>>,
+    marker: PhantomData<&'a mut HashMap<K, (O, V), S>>,
+}
+
+// SAFETY: `ValuesMut` acts as the mutable borrow of the map it was created from.
+unsafe impl<K: Send, O: Send, V: Send, S: Send> Send for ValuesMut<'_, K, O, V, S> {}
+
+// SAFETY: `ValuesMut` gives no access to the map through a shared reference.
+unsafe impl<K, O, V, S> Sync for ValuesMut<'_, K, O, V, S> {}
+
+impl<'a, K: Eq + Hash, O, V, S: BuildHasher> ValuesMut<'a, K, O, V, S> {
+    pub(crate) fn new(values: &'a mut HashMap<K, (O, V), S>) -> Self {
+        Self {
+            values: NonNull::from(values),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the order and a mutable reference to the value of the entry with the given key.
+    ///
+    /// # Safety
+    ///
+    /// The entry must exist and must not have been returned before.
+    unsafe fn get(&mut self, key: &K) -> (&'a O, &'a mut V) {
+        // SAFETY: the map is mutably borrowed for `'a`, and the caller guarantees that no other
+        // reference into this entry was handed out. Looking the entry up only reads the control
+        // bytes and the keys of the table, which are never handed out mutably.
+        let (order, value) = unsafe { (*self.values.as_ptr()).get_mut(key).unwrap() };
+        (order, value)
+    }
+}
+
+/// A mutable iterator over the entries of a [`Map`](crate::Map), ascending by order.
+pub struct IterMut<'a, K, O, V, S = DefaultHashBuilder, G: KeySet<K, S> = HashSet<K, S>>
+where
+    K: 'a,
+    G: 'a,
+{
+    keys: GroupKeys<'a, K, O, S, G>,
+    values: ValuesMut<'a, K, O, V, S>,
+}
+
+impl<'a, K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> IterMut<'a, K, O, V, S, G> {
+    pub(crate) fn new(
+        groups: btree_map::Values<'a, O, G>,
+        values: &'a mut HashMap<K, (O, V), S>,
+    ) -> Self {
+        Self {
+            keys: group_keys(groups, values.len()),
+            values: ValuesMut::new(values),
+        }
+    }
+
+    #[allow(clippy::mut_from_ref)] // The value is borrowed from `self.values`, not the key.
+    fn entry(&mut self, key: &'a K) -> (&'a K, &'a O, &'a mut V) {
+        // SAFETY: every key of the map is in exactly one group of the index, and each key of the
+        // index is yielded once.
+        let (order, value) = unsafe { self.values.get(key) };
+        (key, order, value)
+    }
+}
+
+impl<'a, K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> Iterator
+    for IterMut<'a, K, O, V, S, G>
+{
+    type Item = (&'a K, &'a O, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        Some(self.entry(key))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> DoubleEndedIterator
+    for IterMut<'_, K, O, V, S, G>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next_back()?;
+        Some(self.entry(key))
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> ExactSizeIterator
+    for IterMut<'_, K, O, V, S, G>
+{
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> FusedIterator
+    for IterMut<'_, K, O, V, S, G>
+{
+}
+
+/// An owning iterator over the entries of a [`Map`](crate::Map), ascending by order.
+pub struct IntoIter<K, O, V, S = DefaultHashBuilder, G: KeySet<K, S> = HashSet<K, S>> {
+    keys: IntoGroupKeys<K, O, S, G>,
+    values: HashMap<K, (O, V), S>,
+}
+
+impl<K, O, V, S, G: KeySet<K, S>> IntoIter<K, O, V, S, G> {
+    pub(crate) fn new(groups: btree_map::IntoValues<O, G>, values: HashMap<K, (O, V), S>) -> Self {
+        Self {
+            keys: into_group_keys(groups, values.len()),
+            values,
+        }
+    }
+
+    fn entry(&mut self, key: K) -> (K, O, V)
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+    {
+        let (order, value) = self.values.remove(&key).unwrap();
+        (key, order, value)
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> Iterator for IntoIter<K, O, V, S, G> {
+    type Item = (K, O, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        Some(self.entry(key))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> DoubleEndedIterator
+    for IntoIter<K, O, V, S, G>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next_back()?;
+        Some(self.entry(key))
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> ExactSizeIterator
+    for IntoIter<K, O, V, S, G>
+{
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> FusedIterator
+    for IntoIter<K, O, V, S, G>
+{
+}
+
+/// An iterator over the keys of a [`Map`](crate::Map), ascending by order.
+pub struct Keys<'a, K, O, S = DefaultHashBuilder, G: KeySet<K, S> = HashSet<K, S>>
+where
+    K: 'a,
+    G: 'a,
+{
+    keys: GroupKeys<'a, K, O, S, G>,
+}
+
+impl<'a, K, O, S, G: KeySet<K, S>> Keys<'a, K, O, S, G> {
+    pub(crate) fn new(groups: btree_map::Values<'a, O, G>, len: usize) -> Self {
+        Self {
+            keys: group_keys(groups, len),
+        }
+    }
+}
+
+impl<'a, K, O, S, G: KeySet<K, S>> Iterator for Keys<'a, K, O, S, G> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.keys.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K, O, S, G: KeySet<K, S>> DoubleEndedIterator for Keys<'_, K, O, S, G> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.keys.next_back()
+    }
+}
+
+impl<K, O, S, G: KeySet<K, S>> ExactSizeIterator for Keys<'_, K, O, S, G> {}
+
+impl<K, O, S, G: KeySet<K, S>> FusedIterator for Keys<'_, K, O, S, G> {}
+
+/// An iterator over the values of a [`Map`](crate::Map), ascending by order.
+pub struct Values<'a, K, O, V, S = DefaultHashBuilder, G: KeySet<K, S> = HashSet<K, S>>
+where
+    K: 'a,
+    G: 'a,
+{
+    pub(crate) iter: Iter<'a, K, O, V, S, G>,
+}
+
+impl<'a, K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> Iterator
+    for Values<'a, K, O, V, S, G>
+{
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_key, _order, value) = self.iter.next()?;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> DoubleEndedIterator
+    for Values<'_, K, O, V, S, G>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (_key, _order, value) = self.iter.next_back()?;
+        Some(value)
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> ExactSizeIterator
+    for Values<'_, K, O, V, S, G>
+{
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> FusedIterator
+    for Values<'_, K, O, V, S, G>
+{
+}
+
+/// An iterator over the distinct orders of a [`Map`](crate::Map), ascending.
+pub struct Orders<'a, K, O, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    pub(crate) orders: btree_map::Keys<'a, O, G>,
+    pub(crate) marker: PhantomData<fn(K, S)>,
+}
+
+impl<'a, K, O, S, G> Iterator for Orders<'a, K, O, S, G> {
+    type Item = &'a O;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.orders.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.orders.size_hint()
+    }
+}
+
+impl<K, O, S, G> DoubleEndedIterator for Orders<'_, K, O, S, G> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.orders.next_back()
+    }
+}
+
+impl<K, O, S, G> ExactSizeIterator for Orders<'_, K, O, S, G> {}
+
+impl<K, O, S, G> FusedIterator for Orders<'_, K, O, S, G> {}
+
+/// An iterator over the order groups of a [`Map`](crate::Map), ascending by order. Each group is
+/// yielded as its order and an iterator over its keys and values.
+pub struct Groups<'a, K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    pub(crate) groups: btree_map::Iter<'a, O, G>,
+    pub(crate) values: &'a HashMap<K, (O, V), S>,
+}
+
+impl<'a, K, O, V, S, G: KeySet<K, S>> Groups<'a, K, O, V, S, G> {
+    fn group(&self, keys: &'a G) -> GroupIter<'a, K, O, V, S, G> {
+        GroupIter {
+            keys: keys.iter(),
+            values: self.values,
+        }
+    }
+}
+
+impl<'a, K, O, V, S, G: KeySet<K, S>> Iterator for Groups<'a, K, O, V, S, G> {
+    type Item = (&'a O, GroupIter<'a, K, O, V, S, G>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (order, keys) = self.groups.next()?;
+        Some((order, self.group(keys)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.groups.size_hint()
+    }
+}
+
+impl<K, O, V, S, G: KeySet<K, S>> DoubleEndedIterator for Groups<'_, K, O, V, S, G> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (order, keys) = self.groups.next_back()?;
+        Some((order, self.group(keys)))
+    }
+}
+
+impl<K, O, V, S, G: KeySet<K, S>> ExactSizeIterator for Groups<'_, K, O, V, S, G> {}
+
+impl<K, O, V, S, G: KeySet<K, S>> FusedIterator for Groups<'_, K, O, V, S, G> {}
+
+/// An owning iterator over the order groups of a [`Map`](crate::Map), ascending by order. Each
+/// group is yielded as its order and its keys and values; items in a group are not ordered.
+pub struct IntoGroups<K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    pub(crate) groups: btree_map::IntoIter<O, G>,
+    pub(crate) values: HashMap<K, (O, V), S>,
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> IntoGroups<K, O, V, S, G> {
+    fn group(&mut self, keys: G) -> Vec<(K, V)> {
+        keys.into_keys()
+            .map(|key| {
+                let (_order, value) = self.values.remove(&key).unwrap();
+                (key, value)
+            })
+            .collect()
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> Iterator for IntoGroups<K, O, V, S, G> {
+    type Item = (O, Vec<(K, V)>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (order, keys) = self.groups.next()?;
+        Some((order, self.group(keys)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.groups.size_hint()
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> DoubleEndedIterator
+    for IntoGroups<K, O, V, S, G>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (order, keys) = self.groups.next_back()?;
+        Some((order, self.group(keys)))
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> ExactSizeIterator
+    for IntoGroups<K, O, V, S, G>
+{
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> FusedIterator
+    for IntoGroups<K, O, V, S, G>
+{
+}
+
+/// An iterator over the distinct orders of a [`Map`](crate::Map) with the number of entries
+/// having each, ascending by order.
+pub struct OrderDistribution<'a, K, O, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    pub(crate) groups: btree_map::Iter<'a, O, G>,
+    pub(crate) marker: PhantomData<fn(K, S)>,
+}
+
+impl<'a, K, O, S, G: KeySet<K, S>> Iterator for OrderDistribution<'a, K, O, S, G> {
+    type Item = (&'a O, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (order, keys) = self.groups.next()?;
+        Some((order, keys.len()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.groups.size_hint()
+    }
+}
+
+impl<K, O, S, G: KeySet<K, S>> DoubleEndedIterator for OrderDistribution<'_, K, O, S, G> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (order, keys) = self.groups.next_back()?;
+        Some((order, keys.len()))
+    }
+}
+
+impl<K, O, S, G: KeySet<K, S>> ExactSizeIterator for OrderDistribution<'_, K, O, S, G> {}
+
+impl<K, O, S, G: KeySet<K, S>> FusedIterator for OrderDistribution<'_, K, O, S, G> {}
+
+/// An iterator over the entries of a [`Map`](crate::Map) in arbitrary order.
+pub struct IterUnordered<'a, K, O, V> {
+    pub(crate) iter: hash_map::Iter<'a, K, (O, V)>,
+}
+
+impl<'a, K, O, V> Iterator for IterUnordered<'a, K, O, V> {
+    type Item = (&'a K, &'a O, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, (order, value)) = self.iter.next()?;
+        Some((key, order, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, O, V> ExactSizeIterator for IterUnordered<'_, K, O, V> {}
+
+impl<K, O, V> FusedIterator for IterUnordered<'_, K, O, V> {}
+
+/// A mutable iterator over the entries of a [`Map`](crate::Map) in arbitrary order.
+pub struct IterMutUnordered<'a, K, O, V> {
+    pub(crate) iter: hash_map::IterMut<'a, K, (O, V)>,
+}
+
+impl<'a, K, O, V> Iterator for IterMutUnordered<'a, K, O, V> {
+    type Item = (&'a K, &'a O, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, (order, value)) = self.iter.next()?;
+        Some((key, order, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, O, V> ExactSizeIterator for IterMutUnordered<'_, K, O, V> {}
+
+impl<K, O, V> FusedIterator for IterMutUnordered<'_, K, O, V> {}
+
+/// A mutable iterator over the values of a [`Map`](crate::Map) in arbitrary order.
+pub struct ValuesMutUnordered<'a, K, O, V> {
+    pub(crate) iter: hash_map::ValuesMut<'a, K, (O, V)>,
+}
+
+impl<'a, K, O, V> Iterator for ValuesMutUnordered<'a, K, O, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_order, value) = self.iter.next()?;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K, O, V> ExactSizeIterator for ValuesMutUnordered<'_, K, O, V> {}
+
+impl<K, O, V> FusedIterator for ValuesMutUnordered<'_, K, O, V> {}
+
+/// An iterator over the keys and values of a single order group of a [`Map`](crate::Map). The
+/// entries are not ordered.
+pub struct GroupIter<'a, K, O, V, S = DefaultHashBuilder, G: KeySet<K, S> = HashSet<K, S>>
+where
+    K: 'a,
+    G: 'a,
+{
+    pub(crate) keys: G::Iter<'a>,
+    pub(crate) values: &'a HashMap<K, (O, V), S>,
+}
+
+impl<'a, K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> Iterator
+    for GroupIter<'a, K, O, V, S, G>
+{
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        let (_order, value) = self.values.get(key).unwrap();
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> ExactSizeIterator
+    for GroupIter<'_, K, O, V, S, G>
+{
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> FusedIterator
+    for GroupIter<'_, K, O, V, S, G>
+{
+}
+
+/// A draining iterator over the keys and values of a single order group of a
+/// [`Map`](crate::Map). The entries are not ordered. The group is detached from the order index
+/// up front, and its entries are removed from the map one at a time as they are yielded; the rest
+/// are removed when the iterator is dropped.
+pub struct GroupDrain<'a, K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    G: KeySet<K, S>,
+{
+    keys: G::IntoKeys,
+    values: &'a mut HashMap<K, (O, V), S>,
+}
+
+impl<'a, K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> GroupDrain<'a, K, O, V, S, G> {
+    /// Takes the keys of the already detached group.
+    pub(crate) fn new(keys: G, values: &'a mut HashMap<K, (O, V), S>) -> Self {
+        Self {
+            keys: keys.into_keys(),
+            values,
+        }
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> Iterator
+    for GroupDrain<'_, K, O, V, S, G>
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        let (_order, value) = self.values.remove(&key).unwrap();
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> ExactSizeIterator
+    for GroupDrain<'_, K, O, V, S, G>
+{
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> FusedIterator
+    for GroupDrain<'_, K, O, V, S, G>
+{
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> Drop for GroupDrain<'_, K, O, V, S, G> {
+    fn drop(&mut self) {
+        for key in self.keys.by_ref() {
+            self.values.remove(&key);
+        }
+    }
+}
+
+/// An iterator which removes and yields the entries of a [`Map`](crate::Map) that match a
+/// predicate, ascending by order. Entries with equal orders are not ordered.
+///
+/// Constructed by [`Map::extract_if`](crate::Map::extract_if).
+pub struct ExtractIf<'a, K, O, V, F, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    pub(crate) extract: Extract<'a, K, O, V, S, G>,
+    pub(crate) pred: F,
+}
+
+/// The progress of an [`ExtractIf`] through its map, kept apart from the predicate so the
+/// wrapped maps can drive it with predicates of their own.
+pub(crate) struct Extract<'a, K, O, V, S, G> {
+    pub(crate) map: &'a mut Map<K, O, V, S, G>,
+    order: Option<O>,
+    keys: vec::IntoIter<K>,
+}
+
+impl<'a, K, O, V, S, G: KeySet<K, S>> Extract<'a, K, O, V, S, G>
+where
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    S: BuildHasher + Clone,
+{
+    pub(crate) fn new(map: &'a mut Map<K, O, V, S, G>) -> Self {
+        Self {
+            map,
+            order: None,
+            keys: Vec::new().into_iter(),
+        }
+    }
+
+    pub(crate) fn next<F: FnMut(&K, &O, &mut V) -> bool>(
+        &mut self,
+        mut pred: F,
+    ) -> Option<(K, O, V)> {
+        loop {
+            for key in self.keys.by_ref() {
+                let (order, value) = self.map.values.get_mut(&key).unwrap();
+                if pred(&key, order, value) {
+                    let (order, value) = self.map.remove(&key).unwrap();
+                    return Some((key, order, value));
+                }
+            }
+            let (order, keys) = match &self.order {
+                None => self.map.ordered_keys.first_key_value(),
+                Some(order) => self
+                    .map
+                    .ordered_keys
+                    .range((Bound::Excluded(order), Bound::Unbounded))
+                    .next(),
+            }?;
+            self.order = Some(order.clone());
+            self.keys = keys.iter().cloned().collect::<Vec<_>>().into_iter();
+        }
+    }
+}
+
+impl<K, O, V, F, S, G: KeySet<K, S>> Iterator for ExtractIf<'_, K, O, V, F, S, G>
+where
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    F: FnMut(&K, &O, &mut V) -> bool,
+    S: BuildHasher + Clone,
+{
+    type Item = (K, O, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.extract.next(&mut self.pred)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.extract.map.len()))
+    }
+}
+
+impl<K, O, V, F, S, G: KeySet<K, S>> FusedIterator for ExtractIf<'_, K, O, V, F, S, G>
+where
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    F: FnMut(&K, &O, &mut V) -> bool,
+    S: BuildHasher + Clone,
+{
+}
+
+/// A draining iterator over the entries of a [`Map`](crate::Map), ascending by order. The
+/// entries are removed from the map in place as they are yielded, and the rest are removed when
+/// the iterator is dropped, so the map keeps its allocation. If the iterator is leaked, the
+/// entries it did not yield are left in the map but are no longer visited in order.
+///
+/// Constructed by [`Map::drain`](crate::Map::drain).
+pub struct Drain<'a, K, O, V, S = DefaultHashBuilder, G: KeySet<K, S> = HashSet<K, S>> {
+    keys: IntoGroupKeys<K, O, S, G>,
+    values: &'a mut HashMap<K, (O, V), S>,
+}
+
+impl<'a, K, O, V, S, G: KeySet<K, S>> Drain<'a, K, O, V, S, G> {
+    /// Takes the groups of the already detached order index.
+    pub(crate) fn new(
+        groups: btree_map::IntoValues<O, G>,
+        values: &'a mut HashMap<K, (O, V), S>,
+    ) -> Self {
+        Self {
+            keys: into_group_keys(groups, values.len()),
+            values,
+        }
+    }
+
+    fn entry(&mut self, key: K) -> (K, O, V)
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+    {
+        let (order, value) = self.values.remove(&key).unwrap();
+        (key, order, value)
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> Iterator for Drain<'_, K, O, V, S, G> {
+    type Item = (K, O, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        Some(self.entry(key))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> DoubleEndedIterator
+    for Drain<'_, K, O, V, S, G>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next_back()?;
+        Some(self.entry(key))
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> ExactSizeIterator
+    for Drain<'_, K, O, V, S, G>
+{
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> FusedIterator
+    for Drain<'_, K, O, V, S, G>
+{
+}
+
+impl<K, O, V, S, G: KeySet<K, S>> Drop for Drain<'_, K, O, V, S, G> {
+    fn drop(&mut self) {
+        self.values.clear();
+    }
+}
+
+type RangeKeys<'a, K, O, S, G> = Flatten<
+    iter::Map<btree_map::Range<'a, O, G>, fn((&'a O, &'a G)) -> KeysOf<'a, K, S, G>>,
+    KeysOf<'a, K, S, G>,
+>;
+
+fn range_keys<K, O, S, G: KeySet<K, S>>(
+    range: btree_map::Range<'_, O, G>,
+) -> RangeKeys<'_, K, O, S, G> {
+    let len = range.clone().map(|(_order, keys)| keys.len()).sum();
+    Flatten::new(range.map(|(_order, keys)| keys.iter()), len)
+}
+
+/// An iterator over the entries of a [`Map`](crate::Map) with orders in a range, ascending by
+/// order.
+///
+/// Constructed by [`Map::range`](crate::Map::range).
+pub struct Range<'a, K, O, V, S = DefaultHashBuilder, G: KeySet<K, S> = HashSet<K, S>>
+where
+    K: 'a,
+    G: 'a,
+{
+    keys: RangeKeys<'a, K, O, S, G>,
+    values: &'a HashMap<K, (O, V), S>,
+}
+
+impl<'a, K, O, V, S, G: KeySet<K, S>> Range<'a, K, O, V, S, G> {
+    pub(crate) fn new(
+        range: btree_map::Range<'a, O, G>,
+        values: &'a HashMap<K, (O, V), S>,
+    ) -> Self {
+        Self {
+            keys: range_keys(range),
+            values,
+        }
+    }
+
+    fn entry(&self, key: &'a K) -> (&'a K, &'a O, &'a V)
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+    {
+        let (order, value) = self.values.get(key).unwrap();
+        (key, order, value)
+    }
+}
+
+impl<'a, K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> Iterator
+    for Range<'a, K, O, V, S, G>
+{
+    type Item = (&'a K, &'a O, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        Some(self.entry(key))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> DoubleEndedIterator
+    for Range<'_, K, O, V, S, G>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next_back()?;
+        Some(self.entry(key))
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> ExactSizeIterator
+    for Range<'_, K, O, V, S, G>
+{
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> FusedIterator
+    for Range<'_, K, O, V, S, G>
+{
+}
+
+/// A mutable iterator over the entries of a [`Map`](crate::Map) with orders in a range,
+/// ascending by order.
+///
+/// Constructed by [`Map::range_mut`](crate::Map::range_mut).
+pub struct RangeMut<'a, K, O, V, S = DefaultHashBuilder, G: KeySet<K, S> = HashSet<K, S>>
+where
+    K: 'a,
+    G: 'a,
+{
+    keys: RangeKeys<'a, K, O, S, G>,
+    values: ValuesMut<'a, K, O, V, S>,
+}
+
+impl<'a, K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> RangeMut<'a, K, O, V, S, G> {
+    pub(crate) fn new(
+        groups: btree_map::Range<'a, O, G>,
+        values: &'a mut HashMap<K, (O, V), S>,
+    ) -> Self {
+        Self {
+            keys: range_keys(groups),
+            values: ValuesMut::new(values),
+        }
+    }
+
+    #[allow(clippy::mut_from_ref)] // The value is borrowed from `self.values`, not the key.
+    fn entry(&mut self, key: &'a K) -> (&'a K, &'a O, &'a mut V) {
+        // SAFETY: every key of the map is in exactly one group of the index, and each key of the
+        // range is yielded once.
+        let (order, value) = unsafe { self.values.get(key) };
+        (key, order, value)
+    }
+}
+
+impl<'a, K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> Iterator
+    for RangeMut<'a, K, O, V, S, G>
+{
+    type Item = (&'a K, &'a O, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        Some(self.entry(key))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> DoubleEndedIterator
+    for RangeMut<'_, K, O, V, S, G>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next_back()?;
+        Some(self.entry(key))
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> ExactSizeIterator
+    for RangeMut<'_, K, O, V, S, G>
+{
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> FusedIterator
+    for RangeMut<'_, K, O, V, S, G>
+{
+}
+
+/// A draining iterator over the entries of a [`Map`](crate::Map) with orders in a range,
+/// ascending by order. The entries are removed from the map when the iterator is created, so the
+/// map stays consistent even if the iterator is leaked.
+///
+/// Constructed by [`Map::drain_range`](crate::Map::drain_range).
+pub struct DrainRange<'a, K, O, V, S = DefaultHashBuilder> {
+    entries: vec::IntoIter<(K, O, V)>,
+    marker: PhantomData<&'a mut Map<K, O, V, S>>,
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher> DrainRange<'_, K, O, V, S> {
+    /// Takes the already detached groups of the range and removes their entries from the value
+    /// map.
+    pub(crate) fn new<G: KeySet<K, S>>(
+        groups: BTreeMap<O, G>,
+        values: &mut HashMap<K, (O, V), S>,
+    ) -> Self {
+        let mut entries = Vec::with_capacity(groups.values().map(G::len).sum());
+        for keys in groups.into_values() {
+            for key in keys.into_keys() {
+                let (order, value) = values.remove(&key).unwrap();
+                entries.push((key, order, value));
+            }
+        }
+        Self {
+            entries: entries.into_iter(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<K, O, V, S> Iterator for DrainRange<'_, K, O, V, S> {
+    type Item = (K, O, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.entries.size_hint()
+    }
+}
+
+impl<K, O, V, S> DoubleEndedIterator for DrainRange<'_, K, O, V, S> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries.next_back()
+    }
+}
+
+impl<K, O, V, S> ExactSizeIterator for DrainRange<'_, K, O, V, S> {}
+
+impl<K, O, V, S> FusedIterator for DrainRange<'_, K, O, V, S> {}
+
+/// An owning iterator over the keys of a [`Map`](crate::Map), ascending by order.
+///
+/// Constructed by [`Map::into_keys`](crate::Map::into_keys).
+pub struct IntoKeys<K, O, S = DefaultHashBuilder, G: KeySet<K, S> = HashSet<K, S>> {
+    keys: IntoGroupKeys<K, O, S, G>,
+}
+
+impl<K, O, S, G: KeySet<K, S>> IntoKeys<K, O, S, G> {
+    pub(crate) fn new(groups: btree_map::IntoValues<O, G>, len: usize) -> Self {
+        Self {
+            keys: into_group_keys(groups, len),
+        }
+    }
+}
+
+impl<K, O, S, G: KeySet<K, S>> Iterator for IntoKeys<K, O, S, G> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.keys.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl<K, O, S, G: KeySet<K, S>> DoubleEndedIterator for IntoKeys<K, O, S, G> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.keys.next_back()
+    }
+}
+
+impl<K, O, S, G: KeySet<K, S>> ExactSizeIterator for IntoKeys<K, O, S, G> {}
+
+impl<K, O, S, G: KeySet<K, S>> FusedIterator for IntoKeys<K, O, S, G> {}
+
+/// An owning iterator over the values of a [`Map`](crate::Map), ascending by order.
+///
+/// Constructed by [`Map::into_values`](crate::Map::into_values).
+pub struct IntoValues<K, O, V, S = DefaultHashBuilder, G: KeySet<K, S> = HashSet<K, S>> {
+    pub(crate) iter: IntoIter<K, O, V, S, G>,
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> Iterator for IntoValues<K, O, V, S, G> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_key, _order, value) = self.iter.next()?;
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> DoubleEndedIterator
+    for IntoValues<K, O, V, S, G>
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (_key, _order, value) = self.iter.next_back()?;
+        Some(value)
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> ExactSizeIterator
+    for IntoValues<K, O, V, S, G>
+{
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> FusedIterator
+    for IntoValues<K, O, V, S, G>
+{
+}
@@ -0,0 +1,575 @@
+use alloc::collections::{btree_map, btree_set, BTreeMap, BTreeSet};
+use core::hash::{BuildHasher, Hash};
+use core::iter::{FusedIterator, Rev};
+
+use crate::{hash_map, hash_set, DefaultHashBuilder, HashMap, HashSet, Map};
+
+/// A container for the keys of the entries sharing an order in a [`Map`]. The container decides
+/// in which order those entries are served and visited.
+///
+/// `S` is the hasher of the map, which containers that hash their keys can use.
+pub trait KeySet<K, S>: Sized {
+    /// An iterator over the keys in the order they are served.
+    type Iter<'a>: DoubleEndedIterator<Item = &'a K> + ExactSizeIterator + Clone
+    where
+        Self: 'a,
+        K: 'a;
+
+    /// An owning iterator over the keys in the order they are served.
+    type IntoKeys: DoubleEndedIterator<Item = K> + ExactSizeIterator;
+
+    /// Creates an empty container for a map with the given hasher.
+    fn with_hasher(hasher: &S) -> Self;
+
+    /// Adds a key. Returns `false` if it was already in the container.
+    fn insert(&mut self, key: K) -> bool;
+
+    /// Removes a key. Returns `true` if it was in the container.
+    fn remove(&mut self, key: &K) -> bool;
+
+    /// Removes the key which is served first.
+    fn pop_first(&mut self) -> Option<K>;
+
+    /// Returns the number of keys in the container.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if the container holds no keys.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the keys in the order they are served.
+    fn iter(&self) -> Self::Iter<'_>;
+
+    /// Consumes the container, returning an iterator over the keys in the order they are served.
+    fn into_keys(self) -> Self::IntoKeys;
+
+    /// Keeps only the keys for which `f` returns `true`.
+    fn retain<F: FnMut(&K) -> bool>(&mut self, f: F);
+
+    /// Removes all keys, keeping the allocated memory for reuse.
+    fn clear(&mut self);
+
+    /// Moves the keys of `other`, none of which are in this container, to this container. They
+    /// are served after the keys already here unless the container orders keys by themselves.
+    fn append(&mut self, other: Self) {
+        for key in other.into_keys() {
+            self.insert(key);
+        }
+    }
+
+    /// Shrinks the container as much as possible. Does nothing by default.
+    fn shrink_to_fit(&mut self) {}
+}
+
+/// An iterator over the keys of a [`HashSet`] group, which are in no particular order. Both ends
+/// of the iterator yield them in the same order.
+#[derive(Clone, Debug)]
+pub struct Unordered<I>(I);
+
+impl<I: Iterator> Iterator for Unordered<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<I: Iterator> DoubleEndedIterator for Unordered<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for Unordered<I> {}
+
+impl<I: FusedIterator> FusedIterator for Unordered<I> {}
+
+/// Serves keys in no particular order. This is the default, and the fastest option.
+impl<K: Eq + Hash, S: BuildHasher + Clone> KeySet<K, S> for HashSet<K, S> {
+    type Iter<'a>
+        = Unordered<hash_set::Iter<'a, K>>
+    where
+        K: 'a,
+        S: 'a;
+    type IntoKeys = Unordered<hash_set::IntoIter<K>>;
+
+    fn with_hasher(hasher: &S) -> Self {
+        HashSet::with_hasher(hasher.clone())
+    }
+
+    fn insert(&mut self, key: K) -> bool {
+        HashSet::insert(self, key)
+    }
+
+    fn remove(&mut self, key: &K) -> bool {
+        HashSet::remove(self, key)
+    }
+
+    fn pop_first(&mut self) -> Option<K> {
+        self.extract_if(|_key| true).next()
+    }
+
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        Unordered(HashSet::iter(self))
+    }
+
+    fn into_keys(self) -> Self::IntoKeys {
+        Unordered(self.into_iter())
+    }
+
+    fn retain<F: FnMut(&K) -> bool>(&mut self, f: F) {
+        HashSet::retain(self, f);
+    }
+
+    fn clear(&mut self) {
+        HashSet::clear(self);
+    }
+
+    fn append(&mut self, other: Self) {
+        self.extend(other);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        HashSet::shrink_to_fit(self);
+    }
+}
+
+/// Serves keys in ascending order.
+impl<K: Ord, S> KeySet<K, S> for BTreeSet<K> {
+    type Iter<'a>
+        = btree_set::Iter<'a, K>
+    where
+        K: 'a;
+    type IntoKeys = btree_set::IntoIter<K>;
+
+    fn with_hasher(_hasher: &S) -> Self {
+        BTreeSet::new()
+    }
+
+    fn insert(&mut self, key: K) -> bool {
+        BTreeSet::insert(self, key)
+    }
+
+    fn remove(&mut self, key: &K) -> bool {
+        BTreeSet::remove(self, key)
+    }
+
+    fn pop_first(&mut self) -> Option<K> {
+        BTreeSet::pop_first(self)
+    }
+
+    fn len(&self) -> usize {
+        BTreeSet::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        BTreeSet::iter(self)
+    }
+
+    fn into_keys(self) -> Self::IntoKeys {
+        self.into_iter()
+    }
+
+    fn retain<F: FnMut(&K) -> bool>(&mut self, f: F) {
+        BTreeSet::retain(self, f);
+    }
+
+    fn clear(&mut self) {
+        BTreeSet::clear(self);
+    }
+
+    fn append(&mut self, mut other: Self) {
+        BTreeSet::append(self, &mut other);
+    }
+}
+
+/// A group which serves keys in the order they were added, first in first out. Every operation
+/// takes logarithmic time.
+///
+/// Each key is numbered when it is added. The numbering starts over whenever the group becomes
+/// empty, so it can not run out while the group is in use.
+#[derive(Clone, Debug)]
+pub struct FifoSet<K, S = DefaultHashBuilder> {
+    sequences: HashMap<K, u64, S>,
+    keys: BTreeMap<u64, K>,
+    next: u64,
+}
+
+impl<K, S> FifoSet<K, S> {
+    fn restart_if_empty(&mut self) {
+        if self.keys.is_empty() {
+            self.next = 0;
+        }
+    }
+
+    fn pop_last(&mut self) -> Option<K>
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+    {
+        let (_sequence, key) = self.keys.pop_last()?;
+        self.sequences.remove(&key);
+        self.restart_if_empty();
+        Some(key)
+    }
+}
+
+impl<K: Clone + Eq + Hash, S: BuildHasher + Clone> KeySet<K, S> for FifoSet<K, S> {
+    type Iter<'a>
+        = btree_map::Values<'a, u64, K>
+    where
+        K: 'a,
+        S: 'a;
+    type IntoKeys = btree_map::IntoValues<u64, K>;
+
+    fn with_hasher(hasher: &S) -> Self {
+        Self {
+            sequences: HashMap::with_hasher(hasher.clone()),
+            keys: BTreeMap::new(),
+            next: 0,
+        }
+    }
+
+    fn insert(&mut self, key: K) -> bool {
+        match self.sequences.entry(key) {
+            hash_map::Entry::Occupied(_entry) => false,
+            hash_map::Entry::Vacant(entry) => {
+                self.keys.insert(self.next, entry.key().clone());
+                entry.insert(self.next);
+                self.next += 1;
+                true
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &K) -> bool {
+        let Some(sequence) = self.sequences.remove(key) else {
+            return false;
+        };
+        self.keys.remove(&sequence);
+        self.restart_if_empty();
+        true
+    }
+
+    fn pop_first(&mut self) -> Option<K> {
+        let (_sequence, key) = self.keys.pop_first()?;
+        self.sequences.remove(&key);
+        self.restart_if_empty();
+        Some(key)
+    }
+
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.keys.values()
+    }
+
+    fn into_keys(self) -> Self::IntoKeys {
+        self.keys.into_values()
+    }
+
+    fn retain<F: FnMut(&K) -> bool>(&mut self, mut f: F) {
+        let sequences = &mut self.sequences;
+        self.keys.retain(|_sequence, key| {
+            let keep = f(key);
+            if !keep {
+                sequences.remove(key);
+            }
+            keep
+        });
+        self.restart_if_empty();
+    }
+
+    fn clear(&mut self) {
+        self.sequences.clear();
+        self.keys.clear();
+        self.next = 0;
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.sequences.shrink_to_fit();
+    }
+}
+
+/// A group which serves keys in the reverse of the order they were added, last in first out.
+/// Every operation takes logarithmic time.
+#[derive(Clone, Debug)]
+pub struct LifoSet<K, S = DefaultHashBuilder>(FifoSet<K, S>);
+
+impl<K: Clone + Eq + Hash, S: BuildHasher + Clone> KeySet<K, S> for LifoSet<K, S> {
+    type Iter<'a>
+        = Rev<btree_map::Values<'a, u64, K>>
+    where
+        K: 'a,
+        S: 'a;
+    type IntoKeys = Rev<btree_map::IntoValues<u64, K>>;
+
+    fn with_hasher(hasher: &S) -> Self {
+        Self(FifoSet::with_hasher(hasher))
+    }
+
+    fn insert(&mut self, key: K) -> bool {
+        self.0.insert(key)
+    }
+
+    fn remove(&mut self, key: &K) -> bool {
+        self.0.remove(key)
+    }
+
+    fn pop_first(&mut self) -> Option<K> {
+        self.0.pop_last()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.0.iter().rev()
+    }
+
+    fn into_keys(self) -> Self::IntoKeys {
+        self.0.into_keys().rev()
+    }
+
+    fn retain<F: FnMut(&K) -> bool>(&mut self, f: F) {
+        self.0.retain(f);
+    }
+
+    fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit();
+    }
+}
+
+/// Serves keys in the order they were added. Removing a key other than the first one takes
+/// linear time.
+#[cfg(feature = "indexmap")]
+impl<K: Eq + Hash, S: BuildHasher + Clone> KeySet<K, S> for indexmap::IndexSet<K, S> {
+    type Iter<'a>
+        = indexmap::set::Iter<'a, K>
+    where
+        K: 'a,
+        S: 'a;
+    type IntoKeys = indexmap::set::IntoIter<K>;
+
+    fn with_hasher(hasher: &S) -> Self {
+        indexmap::IndexSet::with_hasher(hasher.clone())
+    }
+
+    fn insert(&mut self, key: K) -> bool {
+        indexmap::IndexSet::insert(self, key)
+    }
+
+    fn remove(&mut self, key: &K) -> bool {
+        self.shift_remove(key)
+    }
+
+    fn pop_first(&mut self) -> Option<K> {
+        self.shift_remove_index(0)
+    }
+
+    fn len(&self) -> usize {
+        indexmap::IndexSet::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        indexmap::IndexSet::iter(self)
+    }
+
+    fn into_keys(self) -> Self::IntoKeys {
+        self.into_iter()
+    }
+
+    fn retain<F: FnMut(&K) -> bool>(&mut self, f: F) {
+        indexmap::IndexSet::retain(self, f);
+    }
+
+    fn clear(&mut self) {
+        indexmap::IndexSet::clear(self);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        indexmap::IndexSet::shrink_to_fit(self);
+    }
+}
+
+/// Serves keys in the order they were added. Finding a key takes linear time, which is fast for
+/// the small groups this is meant for, and groups of up to `A::size()` keys are not allocated.
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array, S> KeySet<A::Item, S> for smallvec::SmallVec<A>
+where
+    A::Item: Eq,
+{
+    type Iter<'a>
+        = core::slice::Iter<'a, A::Item>
+    where
+        A: 'a;
+    type IntoKeys = smallvec::IntoIter<A>;
+
+    fn with_hasher(_hasher: &S) -> Self {
+        smallvec::SmallVec::new()
+    }
+
+    fn insert(&mut self, key: A::Item) -> bool {
+        if self.contains(&key) {
+            return false;
+        }
+        self.push(key);
+        true
+    }
+
+    fn remove(&mut self, key: &A::Item) -> bool {
+        match self.as_slice().iter().position(|other| other == key) {
+            Some(index) => {
+                smallvec::SmallVec::remove(self, index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn pop_first(&mut self) -> Option<A::Item> {
+        if smallvec::SmallVec::is_empty(self) {
+            None
+        } else {
+            Some(smallvec::SmallVec::remove(self, 0))
+        }
+    }
+
+    fn len(&self) -> usize {
+        smallvec::SmallVec::len(self)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        self.as_slice().iter()
+    }
+
+    fn into_keys(self) -> Self::IntoKeys {
+        self.into_iter()
+    }
+
+    fn retain<F: FnMut(&A::Item) -> bool>(&mut self, mut f: F) {
+        smallvec::SmallVec::retain(self, |key| f(key));
+    }
+
+    fn clear(&mut self) {
+        smallvec::SmallVec::clear(self);
+    }
+
+    fn shrink_to_fit(&mut self) {
+        smallvec::SmallVec::shrink_to_fit(self);
+    }
+}
+
+/// A map which keeps the keys of each order in a [`BTreeSet`], so entries with equal orders are
+/// served and visited ascending by key.
+pub type SortedMap<K, O, V, S = DefaultHashBuilder> = Map<K, O, V, S, BTreeSet<K>>;
+
+/// A map which serves entries with equal orders in the order they got their orders, first in
+/// first out. Adding an entry or moving it to another order puts it behind the entries already
+/// there, even if it replaces an entry with the same key; changing only its value keeps its
+/// place.
+pub type FifoMap<K, O, V, S = DefaultHashBuilder> = Map<K, O, V, S, FifoSet<K, S>>;
+
+/// A map which serves entries with equal orders in the reverse of the order they got their
+/// orders, last in first out.
+pub type LifoMap<K, O, V, S = DefaultHashBuilder> = Map<K, O, V, S, LifoSet<K, S>>;
+
+/// A set of keys to keep or remove, accepted by [`Map::retain_keys`] and [`Map::remove_keys`].
+pub trait KeyCollection<K> {
+    /// An iterator over the keys in arbitrary order.
+    type Iter<'a>: Iterator<Item = &'a K>
+    where
+        Self: 'a,
+        K: 'a;
+
+    /// Returns the number of keys.
+    fn len(&self) -> usize;
+
+    /// Returns `true` if there are no keys.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if the key is in the collection.
+    fn contains(&self, key: &K) -> bool;
+
+    /// Returns an iterator over the keys.
+    fn iter(&self) -> Self::Iter<'_>;
+}
+
+impl<K: Eq + Hash, S: BuildHasher> KeyCollection<K> for HashSet<K, S> {
+    type Iter<'a>
+        = hash_set::Iter<'a, K>
+    where
+        Self: 'a,
+        K: 'a;
+
+    fn len(&self) -> usize {
+        HashSet::len(self)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        HashSet::contains(self, key)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        HashSet::iter(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K: Eq + Hash, S: BuildHasher> KeyCollection<K> for std::collections::HashSet<K, S> {
+    type Iter<'a>
+        = std::collections::hash_set::Iter<'a, K>
+    where
+        Self: 'a,
+        K: 'a;
+
+    fn len(&self) -> usize {
+        std::collections::HashSet::len(self)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        std::collections::HashSet::contains(self, key)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        std::collections::HashSet::iter(self)
+    }
+}
+
+impl<K: Ord> KeyCollection<K> for BTreeSet<K> {
+    type Iter<'a>
+        = btree_set::Iter<'a, K>
+    where
+        Self: 'a,
+        K: 'a;
+
+    fn len(&self) -> usize {
+        BTreeSet::len(self)
+    }
+
+    fn contains(&self, key: &K) -> bool {
+        BTreeSet::contains(self, key)
+    }
+
+    fn iter(&self) -> Self::Iter<'_> {
+        BTreeSet::iter(self)
+    }
+}
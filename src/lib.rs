@@ -1,93 +1,3592 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
-use std::hash::Hash;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
-pub struct Map<K, O, V> {
-    values: HashMap<K, (O, V)>,
-    ordered_keys: BTreeMap<O, HashSet<K>>,
+extern crate alloc;
+
+use alloc::collections::{btree_map, BTreeMap};
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{BuildHasher, Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::{Add, Bound, Index, RangeBounds};
+
+pub use hashbrown::TryReserveError;
+use hashbrown::{hash_map, hash_set, HashMap, HashSet};
+
+/// The hasher used by [`Map`] unless another one is given. The `fxhash` and `ahash` features
+/// swap it for a faster one, with `fxhash` taking precedence if both are enabled.
+#[cfg(feature = "fxhash")]
+pub type DefaultHashBuilder = rustc_hash::FxBuildHasher;
+/// The hasher used by [`Map`] unless another one is given. The `fxhash` and `ahash` features
+/// swap it for a faster one, with `fxhash` taking precedence if both are enabled.
+#[cfg(all(feature = "ahash", not(feature = "fxhash")))]
+pub type DefaultHashBuilder = ahash::RandomState;
+/// The hasher used by [`Map`] unless another one is given. The `fxhash` and `ahash` features
+/// swap it for a faster one, with `fxhash` taking precedence if both are enabled.
+#[cfg(not(any(feature = "ahash", feature = "fxhash")))]
+pub type DefaultHashBuilder = hashbrown::DefaultHashBuilder;
+
+/// A map for keys that are already well distributed integers, such as random ids. The keys are
+/// used as their own hashes instead of being hashed; see [`nohash_hasher::IsEnabled`] for the key
+/// types allowed.
+#[cfg(feature = "nohash")]
+pub type IntMap<K, O, V> = Map<K, O, V, nohash_hasher::BuildNoHashHasher<K>>;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+#[cfg(feature = "borsh")]
+mod borsh;
+mod builder;
+mod comparator;
+mod cursor;
+mod entry;
+mod error;
+#[cfg(feature = "heapless")]
+pub mod fixed;
+mod guard;
+mod iter;
+mod key_set;
+mod max;
+mod order;
+mod policy;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "rayon")]
+pub mod rayon;
+#[cfg(feature = "rkyv")]
+pub mod rkyv;
+#[cfg(feature = "schemars")]
+mod schemars;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod tie_break;
+pub mod wrapped;
+
+pub use builder::MapBuilder;
+pub use comparator::ComparatorMap;
+pub use cursor::{Cursor, CursorMut};
+pub use entry::{
+    Entry, OccupiedEntry, OccupiedOrderEntry, OrderEntry, VacantEntry, VacantOrderEntry,
+};
+pub use error::OccupiedError;
+#[cfg(feature = "heapless")]
+pub use fixed::FixedMap;
+pub use guard::{OrderMut, PeekSmallestMut};
+pub use iter::{
+    Drain, DrainRange, ExtractIf, GroupDrain, GroupIter, Groups, IntoGroups, IntoIter, IntoKeys,
+    IntoValues, Iter, IterMut, IterMutUnordered, IterUnordered, Keys, OrderDistribution, Orders,
+    Range, RangeMut, Values, ValuesMutUnordered,
+};
+pub use key_set::{
+    FifoMap, FifoSet, KeyCollection, KeySet, LifoMap, LifoSet, SortedMap, Unordered,
+};
+pub use max::MaxMap;
+pub use order::{Compare, CompareBy, F32Ord, F64Ord, HasOrder};
+pub use policy::DuplicatePolicy;
+pub use tie_break::{ByKey, Fifo, Lifo, TieBreak, Unspecified};
+pub use wrapped::{OrderWrapper, WrappedMap};
+
+pub(crate) fn remove_ordered_key<K, O: Ord, S, G: KeySet<K, S>>(
+    ordered_keys: &mut BTreeMap<O, G>,
+    order: &O,
+    key: &K,
+) {
+    let keys = ordered_keys.get_mut(order).unwrap();
+    assert!(keys.remove(key));
+    if keys.is_empty() {
+        assert!(ordered_keys.remove(order).is_some());
+    }
+}
+
+pub(crate) fn add_ordered_key<K, O: Ord, S, G: KeySet<K, S>>(
+    ordered_keys: &mut BTreeMap<O, G>,
+    hasher: &S,
+    order: O,
+    key: K,
+) {
+    assert!(ordered_keys
+        .entry(order)
+        .or_insert_with(|| G::with_hasher(hasher))
+        .insert(key));
+}
+
+/// Creates a [`Map`] containing the given entries, written as `key => (order, value)`.
+#[macro_export]
+macro_rules! ordmap {
+    ($($key:expr => ($order:expr, $value:expr)),* $(,)?) => {
+        $crate::Map::from([$(($key, $order, $value)),*])
+    };
+}
+
+/// A map from keys to values, each with an order, iterable by order.
+///
+/// Entries are stored in a hash map from keys to orders and values, and indexed by a `BTreeMap`
+/// from orders to groups of keys. All of this memory comes from the global allocator: custom
+/// allocators are not supported, since the allocator parameter of `BTreeMap` is not stable.
+///
+/// The keys of each order are kept in a `G`, whose [`KeySet`] implementation decides in which
+/// order entries with equal orders are served and visited. The default [`HashSet`] groups keep
+/// them in no particular order, which is what the methods mean by entries with equal orders not
+/// being ordered. Maps with other groups are created with [`Default`] or a [`MapBuilder`].
+pub struct Map<K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    values: HashMap<K, (O, V), S>,
+    ordered_keys: BTreeMap<O, G>,
+}
+
+impl<K, O, V> Map<K, O, V> {
+    /// Creates an empty map. Does not allocate. This can not be a `const fn` because the default
+    /// hasher is randomly seeded on creation; see [`Map::with_hasher`].
+    pub fn new() -> Self {
+        Self::with_hasher(DefaultHashBuilder::default())
+    }
+
+    /// Creates an empty map with space for at least `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, DefaultHashBuilder::default())
+    }
+}
+
+impl<K, O, V, S> Map<K, O, V, S> {
+    /// Creates an empty map which uses the given hasher for the keys. Does not allocate.
+    pub const fn with_hasher(hasher: S) -> Self {
+        Self {
+            values: HashMap::with_hasher(hasher),
+            ordered_keys: BTreeMap::new(),
+        }
+    }
+
+    /// Creates an empty map with space for at least `capacity` entries which uses the given hasher
+    /// for the keys.
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        Self {
+            values: HashMap::with_capacity_and_hasher(capacity, hasher),
+            ordered_keys: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K, O, V, S, G> Map<K, O, V, S, G> {
+    /// Creates an empty map with any groups, which the public constructors only offer for the
+    /// default ones so that the groups do not have to be named.
+    pub(crate) fn with_capacity_and_hasher_in(capacity: usize, hasher: S) -> Self {
+        Self {
+            values: HashMap::with_capacity_and_hasher(capacity, hasher),
+            ordered_keys: BTreeMap::new(),
+        }
+    }
+
+    /// Returns a reference to the hasher of the map.
+    pub fn hasher(&self) -> &S {
+        self.values.hasher()
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the number of distinct orders in the map.
+    pub fn group_count(&self) -> usize {
+        self.ordered_keys.len()
+    }
+
+    /// Returns `true` if any entry has exactly the given order.
+    pub fn contains_order<Q: Ord + ?Sized>(&self, order: &Q) -> bool
+    where
+        O: Borrow<Q> + Ord,
+    {
+        self.ordered_keys.contains_key(order)
+    }
+
+    /// Returns the number of entries with exactly the given order.
+    pub fn group_len<Q: Ord + ?Sized>(&self, order: &Q) -> usize
+    where
+        O: Borrow<Q> + Ord,
+        G: KeySet<K, S>,
+    {
+        self.ordered_keys.get(order).map_or(0, G::len)
+    }
+
+    /// Returns the number of entries the map can hold without reallocating the value map. The
+    /// order index allocates per group and has no capacity.
+    pub fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    /// Returns the order index: the sets of keys having each order, ascending by order.
+    pub fn as_groups(&self) -> &BTreeMap<O, G> {
+        &self.ordered_keys
+    }
+
+    /// Returns the value map: the orders and values of the entries by key.
+    pub fn as_unordered(&self) -> &HashMap<K, (O, V), S> {
+        &self.values
+    }
+
+    /// Returns an iterator over the entries, ascending by order. Entries with equal orders are
+    /// not ordered.
+    pub fn iter(&self) -> Iter<'_, K, O, V, S, G>
+    where
+        G: KeySet<K, S>,
+    {
+        Iter::new(self.ordered_keys.values(), &self.values)
+    }
+
+    /// Returns an iterator over the keys, ascending by order.
+    pub fn keys(&self) -> Keys<'_, K, O, S, G>
+    where
+        G: KeySet<K, S>,
+    {
+        Keys::new(self.ordered_keys.values(), self.values.len())
+    }
+
+    /// Returns an iterator over the values, ascending by order.
+    pub fn values(&self) -> Values<'_, K, O, V, S, G>
+    where
+        G: KeySet<K, S>,
+    {
+        Values { iter: self.iter() }
+    }
+
+    /// Returns an iterator over the distinct orders, ascending.
+    pub fn orders(&self) -> Orders<'_, K, O, S, G> {
+        Orders {
+            orders: self.ordered_keys.keys(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the order groups, ascending by order. Each group is yielded as its
+    /// order and an iterator over its keys and values.
+    pub fn groups(&self) -> Groups<'_, K, O, V, S, G>
+    where
+        G: KeySet<K, S>,
+    {
+        Groups {
+            groups: self.ordered_keys.iter(),
+            values: &self.values,
+        }
+    }
+
+    /// Consumes the map, returning an iterator over the order groups ascending by order. Each
+    /// group is yielded as its order and its keys and values; items in a group are not ordered.
+    pub fn into_groups(self) -> IntoGroups<K, O, V, S, G>
+    where
+        G: KeySet<K, S>,
+    {
+        IntoGroups {
+            groups: self.ordered_keys.into_iter(),
+            values: self.values,
+        }
+    }
+
+    /// Returns an iterator over the distinct orders with the number of entries having each,
+    /// ascending by order.
+    pub fn order_distribution(&self) -> OrderDistribution<'_, K, O, S, G>
+    where
+        G: KeySet<K, S>,
+    {
+        OrderDistribution {
+            groups: self.ordered_keys.iter(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the order at the `p`-th percentile of the entries using the nearest-rank method,
+    /// so the result is always the order of some entry. Walks the group sizes, which takes time
+    /// linear in the number of groups below the result.
+    ///
+    /// Panics if `p` is not within `0.0..=100.0`.
+    pub fn percentile_order(&self, p: f64) -> Option<&O>
+    where
+        G: KeySet<K, S>,
+    {
+        assert!((0.0..=100.0).contains(&p), "percentile out of range: {p}");
+        let rank = p / 100.0 * self.len() as f64;
+        let mut index = rank as usize;
+        if index as f64 == rank {
+            index = index.saturating_sub(1);
+        }
+        for (order, keys) in &self.ordered_keys {
+            if index < keys.len() {
+                return Some(order);
+            }
+            index -= keys.len();
+        }
+        None
+    }
+
+    /// Returns the median order of the entries, the lower one of the two middle orders if there
+    /// is an even number of entries.
+    pub fn median_order(&self) -> Option<&O>
+    where
+        G: KeySet<K, S>,
+    {
+        self.percentile_order(50.0)
+    }
+
+    /// Returns the smallest order, without looking at the entries that have it.
+    pub fn min_order(&self) -> Option<&O>
+    where
+        O: Ord,
+    {
+        let (order, _keys) = self.ordered_keys.first_key_value()?;
+        Some(order)
+    }
+
+    /// Returns the largest order, without looking at the entries that have it.
+    pub fn max_order(&self) -> Option<&O>
+    where
+        O: Ord,
+    {
+        let (order, _keys) = self.ordered_keys.last_key_value()?;
+        Some(order)
+    }
+
+    /// Consumes the map, returning an iterator over the keys ascending by order.
+    pub fn into_keys(self) -> IntoKeys<K, O, S, G>
+    where
+        G: KeySet<K, S>,
+    {
+        IntoKeys::new(self.ordered_keys.into_values(), self.values.len())
+    }
+
+    /// Consumes the map, returning an iterator over the values ascending by order.
+    pub fn into_values(self) -> IntoValues<K, O, V, S, G>
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+        G: KeySet<K, S>,
+    {
+        IntoValues {
+            iter: self.into_iter(),
+        }
+    }
+
+    /// Returns an iterator over the entries with mutable references to the values, ascending by
+    /// order. Entries with equal orders are not ordered.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, O, V, S, G>
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+        G: KeySet<K, S>,
+    {
+        IterMut::new(self.ordered_keys.values(), &mut self.values)
+    }
+
+    /// Returns an iterator over the entries in arbitrary order. Cheaper than [`Map::iter`].
+    pub fn iter_unordered(&self) -> IterUnordered<'_, K, O, V> {
+        IterUnordered {
+            iter: self.values.iter(),
+        }
+    }
+
+    /// Returns an iterator over the entries with mutable references to the values in arbitrary
+    /// order. Cheaper than [`Map::iter_mut`].
+    pub fn iter_mut_unordered(&mut self) -> IterMutUnordered<'_, K, O, V> {
+        IterMutUnordered {
+            iter: self.values.iter_mut(),
+        }
+    }
+
+    /// Returns an iterator over mutable references to the values in arbitrary order.
+    pub fn values_mut_unordered(&mut self) -> ValuesMutUnordered<'_, K, O, V> {
+        ValuesMutUnordered {
+            iter: self.values.values_mut(),
+        }
+    }
+
+    /// Removes all entries, returning them as an iterator ascending by order. Entries with equal
+    /// orders are not ordered. The entries are removed in place, so the map keeps its capacity
+    /// without allocating.
+    pub fn drain(&mut self) -> Drain<'_, K, O, V, S, G>
+    where
+        G: KeySet<K, S>,
+    {
+        let ordered_keys = core::mem::take(&mut self.ordered_keys);
+        Drain::new(ordered_keys.into_values(), &mut self.values)
+    }
+
+    /// Removes all entries, keeping the allocated memory for reuse.
+    pub fn clear(&mut self) {
+        self.values.clear();
+        self.ordered_keys.clear();
+    }
+}
+
+impl<K, O, V, S: Default, G> Default for Map<K, O, V, S, G> {
+    /// Creates an empty map.
+    fn default() -> Self {
+        Self::with_capacity_and_hasher_in(0, S::default())
+    }
+}
+
+impl<K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> IntoIterator for Map<K, O, V, S, G> {
+    type Item = (K, O, V);
+    type IntoIter = IntoIter<K, O, V, S, G>;
+
+    /// Consumes the map, yielding the entries ascending by order. Entries with equal orders are
+    /// not ordered.
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.ordered_keys.into_values(), self.values)
+    }
+}
+
+impl<'a, K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> IntoIterator
+    for &'a Map<K, O, V, S, G>
+{
+    type Item = (&'a K, &'a O, &'a V);
+    type IntoIter = Iter<'a, K, O, V, S, G>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K: Eq + Hash, O, V, S: BuildHasher, G: KeySet<K, S>> IntoIterator
+    for &'a mut Map<K, O, V, S, G>
+{
+    type Item = (&'a K, &'a O, &'a mut V);
+    type IntoIter = IterMut<'a, K, O, V, S, G>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
 }
 
-impl<K, O, V> Map<K, O, V> {
-    pub fn new() -> Self {
-        Self {
-            values: HashMap::new(),
-            ordered_keys: BTreeMap::new(),
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>>
+    Map<K, O, V, S, G>
+{
+    fn remove_ordered_key(&mut self, order: &O, key: &K) {
+        remove_ordered_key(&mut self.ordered_keys, order, key);
+    }
+
+    fn from_values(values: HashMap<K, (O, V), S>) -> Self {
+        let mut ordered_keys = BTreeMap::new();
+        for (key, (order, _value)) in &values {
+            add_ordered_key(
+                &mut ordered_keys,
+                values.hasher(),
+                order.clone(),
+                key.clone(),
+            );
+        }
+        Self {
+            values,
+            ordered_keys,
+        }
+    }
+
+    /// Reserves space for at least `additional` more entries in the value map. The groups are
+    /// sized as entries are added to them, since it is not known which orders the entries will
+    /// have.
+    pub fn reserve(&mut self, additional: usize) {
+        self.values.reserve(additional);
+    }
+
+    /// Tries to reserve space for at least `additional` more entries in the value map, returning
+    /// an error instead of aborting if the allocation fails.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.values.try_reserve(additional)
+    }
+
+    /// Shrinks the value map and every group as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.values.shrink_to_fit();
+        for keys in self.ordered_keys.values_mut() {
+            keys.shrink_to_fit();
+        }
+    }
+
+    /// Returns `true` if the map contains an entry with the given key.
+    pub fn contains_key<Q: Eq + Hash + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.values.contains_key(key)
+    }
+
+    /// Returns references to the order and the value of an entry.
+    pub fn get<Q: Eq + Hash + ?Sized>(&self, key: &Q) -> Option<(&O, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        let (order, value) = self.values.get(key)?;
+        Some((order, value))
+    }
+
+    /// Returns a reference to the order of an entry.
+    pub fn get_order<Q: Eq + Hash + ?Sized>(&self, key: &Q) -> Option<&O>
+    where
+        K: Borrow<Q>,
+    {
+        let (order, _value) = self.values.get(key)?;
+        Some(order)
+    }
+
+    /// Returns `true` if the entry with the key has the smallest order, so it would be removed by
+    /// the next [`Map::remove_smallest`].
+    pub fn is_smallest<Q: Eq + Hash + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        match (self.values.get(key), self.ordered_keys.first_key_value()) {
+            (Some((order, _value)), Some((smallest, _keys))) => order == smallest,
+            _ => false,
+        }
+    }
+
+    /// Compares the orders of two entries. Returns `None` if either key has no entry.
+    pub fn cmp_keys<Q: Eq + Hash + ?Sized>(&self, a: &Q, b: &Q) -> Option<Ordering>
+    where
+        K: Borrow<Q>,
+    {
+        let (order_a, _value) = self.values.get(a)?;
+        let (order_b, _value) = self.values.get(b)?;
+        Some(order_a.cmp(order_b))
+    }
+
+    /// Returns a reference to the order and a mutable reference to the value of an entry. The order
+    /// can not be changed this way.
+    pub fn get_mut<Q: Eq + Hash + ?Sized>(&mut self, key: &Q) -> Option<(&O, &mut V)>
+    where
+        K: Borrow<Q>,
+    {
+        let (order, value) = self.values.get_mut(key)?;
+        Some((order, value))
+    }
+
+    /// Returns mutable references to the values of several entries at once, leaving their orders
+    /// untouched. Returns `None` if any key has no entry or if any two keys are equal.
+    pub fn get_many_mut<Q: Eq + Hash + ?Sized, const N: usize>(
+        &mut self,
+        keys: [&Q; N],
+    ) -> Option<[&mut V; N]>
+    where
+        K: Borrow<Q>,
+    {
+        for (index, key) in keys.iter().enumerate() {
+            if keys[..index].contains(key) {
+                return None;
+            }
+        }
+        let entries = self.values.get_many_mut(keys);
+        if entries.iter().any(Option::is_none) {
+            return None;
+        }
+        Some(entries.map(|entry| {
+            let (_order, value) = entry.unwrap();
+            value
+        }))
+    }
+
+    /// Like [`Map::get_many_mut`], but for any number of keys. Collects the value references in a
+    /// single pass over the map, which takes O(n) time.
+    pub fn get_many_mut_slice<Q: Eq + Hash + ?Sized>(&mut self, keys: &[&Q]) -> Option<Vec<&mut V>>
+    where
+        K: Borrow<Q>,
+    {
+        let mut positions = HashMap::with_capacity(keys.len());
+        for (index, key) in keys.iter().enumerate() {
+            if positions.insert(*key, index).is_some() {
+                return None;
+            }
+        }
+        let mut values: Vec<Option<&mut V>> = keys.iter().map(|_key| None).collect();
+        for (key, (_order, value)) in &mut self.values {
+            if let Some(&index) = positions.get(key.borrow()) {
+                values[index] = Some(value);
+            }
+        }
+        values.into_iter().collect()
+    }
+
+    /// Returns a mutable reference to the value of an entry, leaving its order untouched.
+    pub fn get_value_mut<Q: Eq + Hash + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        let (_order, value) = self.values.get_mut(key)?;
+        Some(value)
+    }
+
+    /// Calls `f` on the value of an entry, leaving its order untouched. Returns `false` if there
+    /// is no entry with the given key.
+    pub fn update_value<Q: Eq + Hash + ?Sized, F: FnOnce(&mut V)>(&mut self, key: &Q, f: F) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        match self.get_value_mut(key) {
+            Some(value) => {
+                f(value);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Calls `f` on the value of an entry, then moves the entry to the order derived from the
+    /// value if it changed. Returns `false` if there is no entry with the given key.
+    pub fn update_value_auto<Q: Eq + Hash + ?Sized, F: FnOnce(&mut V)>(
+        &mut self,
+        key: &Q,
+        f: F,
+    ) -> bool
+    where
+        K: Borrow<Q>,
+        V: HasOrder<O>,
+    {
+        self.update(key, |order, value| {
+            f(value);
+            *order = value.order();
+        })
+    }
+
+    /// Calls `f` on the order and the value of an entry, moving the entry to its new group if `f`
+    /// changed the order. Returns `false` if there is no entry with the given key.
+    pub fn update<Q: Eq + Hash + ?Sized, F: FnOnce(&mut O, &mut V)>(
+        &mut self,
+        key: &Q,
+        f: F,
+    ) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        let Some((order, value)) = self.values.get_mut(key) else {
+            return false;
+        };
+        let mut order = order.clone();
+        f(&mut order, value);
+        self.set_order(key, order);
+        true
+    }
+
+    /// Removes an entry by key.
+    pub fn remove<Q: Eq + Hash + ?Sized>(&mut self, key: &Q) -> Option<(O, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let (key, (order, value)) = self.values.remove_entry(key)?;
+        self.remove_ordered_key(&order, &key);
+        Some((order, value))
+    }
+
+    /// Removes the entries with the given keys, returning them in the order of the keys. Keys
+    /// without entries are skipped.
+    pub fn remove_many<'a, Q: Eq + Hash + ?Sized + 'a, I: IntoIterator<Item = &'a Q>>(
+        &mut self,
+        keys: I,
+    ) -> Vec<(K, O, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let mut removed = Vec::new();
+        for key in keys {
+            if let Some((removed_key, (order, value))) = self.values.remove_entry(key) {
+                self.remove_ordered_key(&order, &removed_key);
+                removed.push((removed_key, order, value));
+            }
+        }
+        removed
+    }
+
+    /// Keeps only the entries with keys in `keys`. When `keys` is much smaller than the map, each
+    /// of its keys is looked up and the map is rebuilt from the kept entries; otherwise the map is
+    /// filtered in place, so it keeps its allocations.
+    pub fn retain_keys<C: KeyCollection<K>>(&mut self, keys: &C) {
+        if keys.len().saturating_mul(4) < self.len() {
+            let mut values = HashMap::with_capacity_and_hasher(keys.len(), self.hasher().clone());
+            let mut ordered_keys = BTreeMap::new();
+            for key in keys.iter() {
+                let Some((key, entry)) = self.values.remove_entry(key) else {
+                    continue;
+                };
+                if let Some((order, group)) = self.ordered_keys.remove_entry(&entry.0) {
+                    ordered_keys.insert(order, group);
+                }
+                values.insert(key, entry);
+            }
+            for group in ordered_keys.values_mut() {
+                group.retain(|key| values.contains_key(key));
+            }
+            self.values = values;
+            self.ordered_keys = ordered_keys;
+            return;
+        }
+        self.values.retain(|key, _entry| keys.contains(key));
+        self.ordered_keys.retain(|_order, group| {
+            group.retain(|key| keys.contains(key));
+            !group.is_empty()
+        });
+    }
+
+    /// Removes the entries with keys in `keys`, iterating over whichever of the map and the set is
+    /// smaller.
+    pub fn remove_keys<C: KeyCollection<K>>(&mut self, keys: &C) {
+        if keys.len() >= self.len() {
+            self.retain(|key, _order, _value| !keys.contains(key));
+            return;
+        }
+        for key in keys.iter() {
+            self.remove(key);
+        }
+    }
+
+    /// Keeps only the entries for which `f` returns `true`. The entries are visited in arbitrary
+    /// order.
+    pub fn retain<F: FnMut(&K, &O, &mut V) -> bool>(&mut self, mut f: F) {
+        let ordered_keys = &mut self.ordered_keys;
+        self.values.retain(|key, (order, value)| {
+            let keep = f(key, order, value);
+            if !keep {
+                remove_ordered_key(ordered_keys, order, key);
+            }
+            keep
+        });
+    }
+
+    /// Returns an iterator which removes and yields the entries for which `pred` returns `true`,
+    /// ascending by order. Entries with equal orders are not ordered. If the iterator is dropped
+    /// before being exhausted, the remaining entries are kept.
+    pub fn extract_if<F: FnMut(&K, &O, &mut V) -> bool>(
+        &mut self,
+        pred: F,
+    ) -> ExtractIf<'_, K, O, V, F, S, G> {
+        ExtractIf {
+            extract: iter::Extract::new(self),
+            pred,
+        }
+    }
+
+    /// Returns an iterator over the entries with orders in the range, ascending by order. Entries
+    /// with equal orders are not ordered.
+    ///
+    /// Panics if the range start is greater than the range end, or if they are equal and both
+    /// excluded.
+    pub fn range<Q: Ord + ?Sized, R: RangeBounds<Q>>(&self, range: R) -> Range<'_, K, O, V, S, G>
+    where
+        O: Borrow<Q>,
+    {
+        Range::new(self.ordered_keys.range(range), &self.values)
+    }
+
+    /// Returns an iterator over the entries with orders in the range with mutable references to
+    /// the values, ascending by order. Entries with equal orders are not ordered.
+    ///
+    /// Panics if the range start is greater than the range end, or if they are equal and both
+    /// excluded.
+    pub fn range_mut<Q: Ord + ?Sized, R: RangeBounds<Q>>(
+        &mut self,
+        range: R,
+    ) -> RangeMut<'_, K, O, V, S, G>
+    where
+        O: Borrow<Q>,
+    {
+        RangeMut::new(self.ordered_keys.range(range), &mut self.values)
+    }
+
+    /// Returns a cursor at the group with the smallest order above `bound`, or at the ghost
+    /// position if there is none.
+    pub fn lower_bound<Q: Ord + ?Sized>(&self, bound: Bound<&Q>) -> Cursor<'_, K, O, V, S, G>
+    where
+        O: Borrow<Q>,
+    {
+        let order = self.ordered_keys.range((bound, Bound::Unbounded)).next();
+        Cursor {
+            map: self,
+            order: order.map(|(order, _keys)| order),
+        }
+    }
+
+    /// Returns a cursor at the group with the largest order below `bound`, or at the ghost
+    /// position if there is none.
+    pub fn upper_bound<Q: Ord + ?Sized>(&self, bound: Bound<&Q>) -> Cursor<'_, K, O, V, S, G>
+    where
+        O: Borrow<Q>,
+    {
+        let order = self
+            .ordered_keys
+            .range((Bound::Unbounded, bound))
+            .next_back();
+        Cursor {
+            map: self,
+            order: order.map(|(order, _keys)| order),
+        }
+    }
+
+    /// Returns a cursor like [`Map::lower_bound`] which can also edit the map.
+    pub fn lower_bound_mut<Q: Ord + ?Sized>(
+        &mut self,
+        bound: Bound<&Q>,
+    ) -> CursorMut<'_, K, O, V, S, G>
+    where
+        O: Borrow<Q>,
+    {
+        let order = self.lower_bound(bound).order().cloned();
+        CursorMut { map: self, order }
+    }
+
+    /// Returns a cursor like [`Map::upper_bound`] which can also edit the map.
+    pub fn upper_bound_mut<Q: Ord + ?Sized>(
+        &mut self,
+        bound: Bound<&Q>,
+    ) -> CursorMut<'_, K, O, V, S, G>
+    where
+        O: Borrow<Q>,
+    {
+        let order = self.upper_bound(bound).order().cloned();
+        CursorMut { map: self, order }
+    }
+
+    /// Returns the number of entries with orders in the range without visiting the entries
+    /// themselves. Takes time linear in the number of groups in the range.
+    ///
+    /// Panics if the range start is greater than the range end, or if they are equal and both
+    /// excluded.
+    pub fn count_in_range<Q: Ord + ?Sized, R: RangeBounds<Q>>(&self, range: R) -> usize
+    where
+        O: Borrow<Q>,
+    {
+        self.ordered_keys
+            .range(range)
+            .map(|(_order, keys)| keys.len())
+            .sum()
+    }
+
+    /// Returns the number of entries with orders less than `order`.
+    pub fn count_below<Q: Ord + ?Sized>(&self, order: &Q) -> usize
+    where
+        O: Borrow<Q>,
+    {
+        self.count_in_range((Bound::Unbounded, Bound::Excluded(order)))
+    }
+
+    /// Removes the entries with orders in the range, returning them as an iterator ascending by
+    /// order. Entries with equal orders are not ordered.
+    ///
+    /// Panics if the range start is greater than the range end, or if they are equal and both
+    /// excluded.
+    pub fn drain_range<Q: Ord + ?Sized, R: RangeBounds<Q>>(
+        &mut self,
+        range: R,
+    ) -> DrainRange<'_, K, O, V, S>
+    where
+        O: Borrow<Q>,
+    {
+        let orders: Vec<O> = self
+            .ordered_keys
+            .range(range)
+            .map(|(order, _keys)| order.clone())
+            .collect();
+        let groups = orders
+            .iter()
+            .map(|order| self.ordered_keys.remove_entry::<O>(order).unwrap())
+            .collect();
+        DrainRange::new(groups, &mut self.values)
+    }
+
+    /// Removes the entries with orders less than or equal to `order`, returning them as an
+    /// iterator ascending by order. Entries with equal orders are not ordered.
+    pub fn drain_up_to<Q: Ord + ?Sized>(&mut self, order: &Q) -> DrainRange<'_, K, O, V, S>
+    where
+        O: Borrow<Q>,
+    {
+        self.drain_range((Bound::Unbounded, Bound::Included(order)))
+    }
+
+    /// Removes the entries with orders less than `order`, returning them as an iterator ascending
+    /// by order. Entries with equal orders are not ordered.
+    pub fn drain_below<Q: Ord + ?Sized>(&mut self, order: &Q) -> DrainRange<'_, K, O, V, S>
+    where
+        O: Borrow<Q>,
+    {
+        self.drain_range((Bound::Unbounded, Bound::Excluded(order)))
+    }
+
+    /// Splits the map in two at the given order. Returns a map with the entries whose orders are
+    /// greater than or equal to `order`, keeping the rest.
+    pub fn split_off<Q: Ord + ?Sized>(&mut self, order: &Q) -> Self
+    where
+        O: Borrow<Q>,
+    {
+        let ordered_keys = self.ordered_keys.split_off(order);
+        let mut values = HashMap::with_hasher(self.values.hasher().clone());
+        for key in ordered_keys.values().flat_map(G::iter) {
+            let (key, entry) = self.values.remove_entry(key).unwrap();
+            values.insert(key, entry);
+        }
+        Self {
+            values,
+            ordered_keys,
+        }
+    }
+
+    /// Moves all entries from `other` into `self`, leaving `other` empty. Entries of `other`
+    /// overwrite the entries of `self` with the same keys.
+    pub fn append(&mut self, other: &mut Self) {
+        if self.is_empty() {
+            core::mem::swap(self, other);
+            return;
+        }
+        for (key, order, value) in other.drain() {
+            self.add(key, order, value);
+        }
+    }
+
+    /// Moves all entries from `other` into `self`. For keys present in both maps, `f` receives
+    /// the key, the entry of `self` and the entry of `other`, and returns the resulting entry.
+    pub fn merge_with<F: FnMut(&K, (O, V), (O, V)) -> (O, V)>(&mut self, other: Self, mut f: F) {
+        for (key, order, value) in other {
+            let (order, value) = match self.remove(&key) {
+                Some(old_entry) => f(&key, old_entry, (order, value)),
+                None => (order, value),
+            };
+            self.add(key, order, value);
+        }
+    }
+
+    /// Consumes the map, returning a map with the orders replaced by `f` of the key and the order.
+    /// The entries are visited in arbitrary order.
+    pub fn map_orders<O2: Clone + Ord, F: FnMut(&K, O) -> O2>(
+        self,
+        mut f: F,
+    ) -> Map<K, O2, V, S, G> {
+        let mut values =
+            HashMap::with_capacity_and_hasher(self.len(), self.values.hasher().clone());
+        values.extend(self.values.into_iter().map(|(key, (order, value))| {
+            let order = f(&key, order);
+            (key, (order, value))
+        }));
+        Map::from_values(values)
+    }
+
+    /// Consumes the map, returning a map with the values replaced by `f` of the key, the order
+    /// and the value. The order index is kept as it is. The entries are visited in arbitrary
+    /// order.
+    pub fn map_values<V2, F: FnMut(&K, &O, V) -> V2>(self, mut f: F) -> Map<K, O, V2, S, G> {
+        let mut values =
+            HashMap::with_capacity_and_hasher(self.len(), self.values.hasher().clone());
+        values.extend(self.values.into_iter().map(|(key, (order, value))| {
+            let value = f(&key, &order, value);
+            (key, (order, value))
+        }));
+        Map {
+            values,
+            ordered_keys: self.ordered_keys,
+        }
+    }
+
+    /// Consumes the map, returning a map with the keys replaced by `f` of them. If `f` maps
+    /// several keys to the same key, the entry with the largest order is kept, and an arbitrary
+    /// one of them if their orders are equal. The returned map has the default groups, since
+    /// the groups of this one hold the old keys.
+    pub fn map_keys<K2: Clone + Eq + Hash, F: FnMut(K) -> K2>(self, mut f: F) -> Map<K2, O, V, S> {
+        let mut map = Map::with_capacity_and_hasher(self.len(), self.values.hasher().clone());
+        for (key, order, value) in self {
+            map.add(f(key), order, value);
+        }
+        map
+    }
+
+    /// Consumes the map, returning a map with the orders replaced by their dense ranks: the
+    /// entries with the smallest order get 0, those with the next one get 1 and so on.
+    pub fn normalize_orders(mut self) -> Map<K, usize, V, S, G> {
+        let mut values =
+            HashMap::with_capacity_and_hasher(self.len(), self.values.hasher().clone());
+        let mut ordered_keys = BTreeMap::new();
+        for (rank, keys) in self.ordered_keys.into_values().enumerate() {
+            for key in keys.iter() {
+                let (key, (_order, value)) = self.values.remove_entry(key).unwrap();
+                values.insert(key, (rank, value));
+            }
+            ordered_keys.insert(rank, keys);
+        }
+        Map {
+            values,
+            ordered_keys,
+        }
+    }
+
+    fn remove_group_into(&mut self, keys: G, group: &mut Vec<(K, V)>) {
+        group.reserve(keys.len());
+        for key in keys.into_keys() {
+            let (_order, value) = self.values.remove(&key).unwrap();
+            group.push((key, value));
+        }
+    }
+
+    fn remove_group(&mut self, order: O, keys: G) -> (O, Vec<(K, V)>) {
+        let mut group = Vec::new();
+        self.remove_group_into(keys, &mut group);
+        (order, group)
+    }
+
+    fn peek_group_into<'a>(&'a self, keys: &'a G, group: &mut Vec<(&'a K, &'a V)>) {
+        group.reserve(keys.len());
+        for key in keys.iter() {
+            let (_order, value) = self.values.get(key).unwrap();
+            group.push((key, value));
+        }
+    }
+
+    fn peek_group<'a>(&'a self, order: &'a O, keys: &'a G) -> (&'a O, Vec<(&'a K, &'a V)>) {
+        let mut group = Vec::new();
+        self.peek_group_into(keys, &mut group);
+        (order, group)
+    }
+
+    /// Returns the `n`-th smallest distinct order, counting from zero.
+    pub fn nth_smallest_order(&self, n: usize) -> Option<&O> {
+        self.ordered_keys.keys().nth(n)
+    }
+
+    /// Returns the `n`-th entry ascending by order, counting from zero. Entries with equal orders
+    /// are not ordered. Skips whole groups, so it takes time linear in the number of groups
+    /// rather than in `n`.
+    pub fn nth_smallest_entry(&self, mut n: usize) -> Option<(&K, &O, &V)> {
+        for (order, keys) in &self.ordered_keys {
+            if n < keys.len() {
+                let key = keys.iter().nth(n).unwrap();
+                let (_order, value) = self.values.get(key).unwrap();
+                return Some((key, order, value));
+            }
+            n -= keys.len();
+        }
+        None
+    }
+
+    /// Returns the number of entries with orders smaller than the order of the entry with the key,
+    /// which is its position in a queue served by [`Map::remove_smallest`]. Entries with equal
+    /// orders share the position. Takes time linear in the number of preceding groups.
+    pub fn rank<Q: Eq + Hash + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        let (order, _value) = self.values.get(key)?;
+        Some(self.count_below(order))
+    }
+
+    /// Removes entries with the smallest order value. Items in the result are not ordered.
+    pub fn remove_smallest(&mut self) -> Option<(O, Vec<(K, V)>)> {
+        let (order, keys) = self.ordered_keys.pop_first()?;
+        Some(self.remove_group(order, keys))
+    }
+
+    /// Removes a single entry with the smallest order value, the one of its group which is served
+    /// first.
+    pub fn pop_one_smallest(&mut self) -> Option<(K, O, V)> {
+        let mut group = self.ordered_keys.first_entry()?;
+        let key = group.get_mut().pop_first().unwrap();
+        if group.get().is_empty() {
+            group.remove();
+        }
+        let (order, value) = self.values.remove(&key).unwrap();
+        Some((key, order, value))
+    }
+
+    /// Returns a guard giving mutable access to an entry with the smallest order, which can also
+    /// move the entry to another order. The entry of the group which is served first is chosen.
+    pub fn peek_smallest_mut(&mut self) -> Option<PeekSmallestMut<'_, K, O, V, S, G>> {
+        let (_order, keys) = self.ordered_keys.first_key_value()?;
+        let key = keys.iter().next().unwrap().clone();
+        Some(PeekSmallestMut {
+            map: self,
+            key,
+            new_order: None,
+        })
+    }
+
+    /// Removes up to `n` entries with the smallest orders, taking whole groups while they fit and
+    /// then the entries of the next group which are served first. Returns the entries ascending
+    /// by order; entries with equal orders are not ordered.
+    pub fn remove_smallest_n(&mut self, n: usize) -> Vec<(K, O, V)> {
+        let mut entries = Vec::with_capacity(n.min(self.len()));
+        while let Some(mut group) = self.ordered_keys.first_entry() {
+            let remaining = n - entries.len();
+            if group.get().len() > remaining {
+                for _ in 0..remaining {
+                    let key = group.get_mut().pop_first().unwrap();
+                    let (order, value) = self.values.remove(&key).unwrap();
+                    entries.push((key, order, value));
+                }
+                break;
+            }
+            let (order, keys) = group.remove_entry();
+            for key in keys.into_keys() {
+                let (_order, value) = self.values.remove(&key).unwrap();
+                entries.push((key, order.clone(), value));
+            }
+        }
+        entries
+    }
+
+    /// Removes entries ascending by order for as long as `pred` returns `true`, stopping at the
+    /// first entry for which it returns `false`, which is kept. Entries with equal orders are
+    /// visited in arbitrary order. Returns the removed entries.
+    pub fn remove_smallest_while<F: FnMut(&K, &O, &V) -> bool>(
+        &mut self,
+        mut pred: F,
+    ) -> Vec<(K, O, V)> {
+        let mut entries = Vec::new();
+        while let Some(mut group) = self.ordered_keys.first_entry() {
+            let key = group.get().iter().next().unwrap();
+            let (order, value) = self.values.get(key).unwrap();
+            if !pred(key, order, value) {
+                break;
+            }
+            let key = key.clone();
+            group.get_mut().remove(&key);
+            if group.get().is_empty() {
+                group.remove();
+            }
+            let (order, value) = self.values.remove(&key).unwrap();
+            entries.push((key, order, value));
+        }
+        entries
+    }
+
+    /// Removes entries with the smallest order value, appending them to `group` in no particular
+    /// order. Returns the order, or `None` if the map is empty. Lets a caller reuse one buffer
+    /// across calls.
+    pub fn remove_smallest_into(&mut self, group: &mut Vec<(K, V)>) -> Option<O> {
+        let (order, keys) = self.ordered_keys.pop_first()?;
+        self.remove_group_into(keys, group);
+        Some(order)
+    }
+
+    /// Appends references to entries with the smallest order value to `group` in no particular
+    /// order. Returns the order, or `None` if the map is empty.
+    pub fn peek_smallest_into<'a>(&'a self, group: &mut Vec<(&'a K, &'a V)>) -> Option<&'a O> {
+        let (order, keys) = self.ordered_keys.first_key_value()?;
+        self.peek_group_into(keys, group);
+        Some(order)
+    }
+
+    /// Returns references to entries with the smallest order value. The references are unordered.
+    pub fn peek_smallest(&self) -> Option<(&O, Vec<(&K, &V)>)> {
+        let (order, keys) = self.ordered_keys.first_key_value()?;
+        Some(self.peek_group(order, keys))
+    }
+
+    /// Returns the smallest order value and an iterator over the keys and values of its entries,
+    /// without allocating. The entries are not ordered.
+    #[allow(clippy::type_complexity)]
+    pub fn peek_smallest_iter(&self) -> Option<(&O, GroupIter<'_, K, O, V, S, G>)> {
+        let (order, keys) = self.ordered_keys.first_key_value()?;
+        let iter = GroupIter {
+            keys: keys.iter(),
+            values: &self.values,
+        };
+        Some((order, iter))
+    }
+
+    /// Returns an iterator over the keys and values of the entries with exactly the given order,
+    /// or `None` if there are none. The entries are not ordered.
+    pub fn keys_with_order<Q: Ord + ?Sized>(
+        &self,
+        order: &Q,
+    ) -> Option<GroupIter<'_, K, O, V, S, G>>
+    where
+        O: Borrow<Q>,
+    {
+        let keys = self.ordered_keys.get(order)?;
+        Some(GroupIter {
+            keys: keys.iter(),
+            values: &self.values,
+        })
+    }
+
+    /// Removes the group with the smallest order value, returning the order and an iterator over
+    /// the entries of the group. The entries are not ordered.
+    #[allow(clippy::type_complexity)]
+    pub fn drain_smallest(&mut self) -> Option<(O, GroupDrain<'_, K, O, V, S, G>)> {
+        let (order, keys) = self.ordered_keys.pop_first()?;
+        Some((order, GroupDrain::new(keys, &mut self.values)))
+    }
+
+    /// Removes entries with the largest order value. Items in the result are not ordered.
+    pub fn remove_largest(&mut self) -> Option<(O, Vec<(K, V)>)> {
+        let (order, keys) = self.ordered_keys.pop_last()?;
+        Some(self.remove_group(order, keys))
+    }
+
+    /// Returns references to entries with the largest order value. The references are unordered.
+    pub fn peek_largest(&self) -> Option<(&O, Vec<(&K, &V)>)> {
+        let (order, keys) = self.ordered_keys.last_key_value()?;
+        Some(self.peek_group(order, keys))
+    }
+
+    /// Returns the old entry with the same key if there was one.
+    pub fn add(&mut self, key: K, order: O, value: V) -> Option<(O, V)> {
+        let old_entry = if let Some((old_order, old_value)) = self.values.remove(&key) {
+            self.remove_ordered_key(&old_order, &key);
+            Some((old_order, old_value))
+        } else {
+            None
+        };
+        add_ordered_key(
+            &mut self.ordered_keys,
+            self.values.hasher(),
+            order.clone(),
+            key.clone(),
+        );
+        assert!(self.values.insert(key, (order, value)).is_none());
+        old_entry
+    }
+
+    /// Changes the key of an entry, keeping its order and value. Returns `false` without changing
+    /// anything if there is no entry with the old key or there already is one with the new key.
+    pub fn rename_key<Q: Eq + Hash + ?Sized>(&mut self, old: &Q, new: K) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        if new.borrow() == old {
+            return self.values.contains_key(old);
+        }
+        if self.values.contains_key::<K>(&new) {
+            return false;
+        }
+        let Some((old, (order, value))) = self.values.remove_entry(old) else {
+            return false;
+        };
+        let keys = self.ordered_keys.get_mut(&order).unwrap();
+        assert!(keys.remove(&old));
+        assert!(keys.insert(new.clone()));
+        assert!(self.values.insert(new, (order, value)).is_none());
+        true
+    }
+
+    /// Moves an entry to a new order, leaving its value untouched. Returns the old order, or
+    /// `None` if there is no entry with the given key.
+    pub fn set_order<Q: Eq + Hash + ?Sized>(&mut self, key: &Q, order: O) -> Option<O>
+    where
+        K: Borrow<Q>,
+    {
+        let (key, (current_order, _value)) = self.values.get_key_value_mut(key)?;
+        if *current_order == order {
+            return Some(order);
+        }
+        let old_order = core::mem::replace(current_order, order.clone());
+        let key = key.clone();
+        remove_ordered_key(&mut self.ordered_keys, &old_order, &key);
+        add_ordered_key(&mut self.ordered_keys, self.values.hasher(), order, key);
+        Some(old_order)
+    }
+
+    /// Returns a guard dereferencing to the order of an entry. Changes made through the guard are
+    /// applied to the map when it is dropped.
+    pub fn order_mut<Q: Eq + Hash + ?Sized>(
+        &mut self,
+        key: &Q,
+    ) -> Option<OrderMut<'_, K, O, V, S, G>>
+    where
+        K: Borrow<Q>,
+    {
+        let (key, (order, _value)) = self.values.get_key_value(key)?;
+        Some(OrderMut {
+            key: key.clone(),
+            order: order.clone(),
+            map: self,
+        })
+    }
+
+    /// Moves an entry to its order plus `delta`, leaving its value untouched. Returns the old
+    /// order, or `None` if there is no entry with the given key.
+    pub fn adjust_order<Q: Eq + Hash + ?Sized>(&mut self, key: &Q, delta: O) -> Option<O>
+    where
+        K: Borrow<Q>,
+        O: Add<Output = O>,
+    {
+        let (order, _value) = self.values.get(key)?;
+        let order = order.clone() + delta;
+        self.set_order(key, order)
+    }
+
+    /// Exchanges the orders of two entries. Returns `false` without changing anything if either
+    /// key has no entry.
+    pub fn swap_orders<Q: Eq + Hash + ?Sized>(&mut self, a: &Q, b: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        let (Some((order_a, _)), Some((order_b, _))) = (self.values.get(a), self.values.get(b))
+        else {
+            return false;
+        };
+        let (order_a, order_b) = (order_a.clone(), order_b.clone());
+        self.set_order(a, order_b);
+        self.set_order(b, order_a);
+        true
+    }
+
+    /// Replaces the order of every entry with `f` of it, calling `f` once per group. Groups that
+    /// end up with the same order are merged. Useful for aging priorities.
+    pub fn age<F: FnMut(O) -> O>(&mut self, mut f: F) {
+        let mut ordered_keys = BTreeMap::new();
+        for (order, keys) in core::mem::take(&mut self.ordered_keys) {
+            let order = f(order);
+            for key in keys.iter() {
+                let (entry_order, _value) = self.values.get_mut(key).unwrap();
+                *entry_order = order.clone();
+            }
+            match ordered_keys.entry(order) {
+                btree_map::Entry::Vacant(entry) => {
+                    entry.insert(keys);
+                }
+                btree_map::Entry::Occupied(mut entry) => entry.get_mut().append(keys),
+            }
+        }
+        self.ordered_keys = ordered_keys;
+    }
+
+    /// Adds `delta` to the order of every entry.
+    pub fn shift_all_orders(&mut self, delta: O)
+    where
+        O: Add<Output = O>,
+    {
+        self.age(|order| order + delta.clone());
+    }
+
+    /// Moves an entry to a new order if it is smaller than the current one, which is the
+    /// decrease-key operation of a priority queue. Returns `true` if the order was changed.
+    pub fn change_order_if_smaller<Q: Eq + Hash + ?Sized>(&mut self, key: &Q, order: O) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        match self.values.get(key) {
+            Some((current_order, _value)) if order < *current_order => {
+                self.set_order(key, order);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Moves an entry to a new order if it is larger than the current one. Returns `true` if the
+    /// order was changed.
+    pub fn change_order_if_larger<Q: Eq + Hash + ?Sized>(&mut self, key: &Q, order: O) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        match self.values.get(key) {
+            Some((current_order, _value)) if order > *current_order => {
+                self.set_order(key, order);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Adds all entries, reserving space for them up front. Returns the entries that were
+    /// replaced by entries with the same keys, in the order they were replaced.
+    pub fn add_batch<I: IntoIterator<Item = (K, O, V)>>(&mut self, iter: I) -> Vec<(K, O, V)> {
+        let iter = iter.into_iter();
+        let (lower, _upper) = iter.size_hint();
+        self.values.reserve(lower);
+        let hasher = self.values.hasher().clone();
+        let mut replaced = Vec::new();
+        for (key, order, value) in iter {
+            match self.values.entry(key) {
+                hash_map::Entry::Occupied(mut entry) => {
+                    let (old_order, old_value) = entry.insert((order.clone(), value));
+                    if old_order != order {
+                        remove_ordered_key(&mut self.ordered_keys, &old_order, entry.key());
+                        add_ordered_key(
+                            &mut self.ordered_keys,
+                            &hasher,
+                            order,
+                            entry.key().clone(),
+                        );
+                    }
+                    replaced.push((entry.key().clone(), old_order, old_value));
+                }
+                hash_map::Entry::Vacant(entry) => {
+                    add_ordered_key(
+                        &mut self.ordered_keys,
+                        &hasher,
+                        order.clone(),
+                        entry.key().clone(),
+                    );
+                    entry.insert((order, value));
+                }
+            }
+        }
+        replaced
+    }
+
+    /// Adds an entry with the order derived from the value. Returns the old entry with the same
+    /// key if there was one.
+    pub fn add_auto(&mut self, key: K, value: V) -> Option<(O, V)>
+    where
+        V: HasOrder<O>,
+    {
+        let order = value.order();
+        self.add(key, order, value)
+    }
+
+    /// Adds an entry only if there is no entry with the same key. Otherwise returns the entry
+    /// back in the error, leaving the map untouched.
+    pub fn try_add(&mut self, key: K, order: O, value: V) -> Result<(), OccupiedError<K, O, V>> {
+        if self.values.contains_key(&key) {
+            return Err(OccupiedError { key, order, value });
+        }
+        self.add(key, order, value);
+        Ok(())
+    }
+
+    /// Adds an entry only if there is no entry with the same key. Returns `true` if the entry was
+    /// added.
+    pub fn add_if_absent(&mut self, key: K, order: O, value: V) -> bool {
+        self.try_add(key, order, value).is_ok()
+    }
+
+    /// Adds an entry, resolving a clash with an existing entry with the same key by the policy.
+    /// Returns the entry back in the error if the policy is [`DuplicatePolicy::Error`].
+    pub fn add_with_policy(
+        &mut self,
+        key: K,
+        order: O,
+        value: V,
+        policy: DuplicatePolicy<'_, K, O, V>,
+    ) -> Result<(), OccupiedError<K, O, V>> {
+        if !self.values.contains_key(&key) {
+            self.add(key, order, value);
+            return Ok(());
+        }
+        match policy {
+            DuplicatePolicy::Overwrite => {
+                self.add(key, order, value);
+            }
+            DuplicatePolicy::KeepExisting => {}
+            DuplicatePolicy::Error => return Err(OccupiedError { key, order, value }),
+            DuplicatePolicy::Merge(f) => {
+                let old_entry = self.remove(&key).unwrap();
+                let (order, value) = f(&key, old_entry, (order, value));
+                self.add(key, order, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the entries one by one with [`Map::add_with_policy`]. Stops at the first error,
+    /// keeping the entries added before it.
+    pub fn extend_with_policy<I: IntoIterator<Item = (K, O, V)>>(
+        &mut self,
+        iter: I,
+        mut policy: DuplicatePolicy<'_, K, O, V>,
+    ) -> Result<(), OccupiedError<K, O, V>> {
+        for (key, order, value) in iter {
+            self.add_with_policy(key, order, value, policy.reborrow())?;
+        }
+        Ok(())
+    }
+
+    /// Collects the entries into a map with [`Map::extend_with_policy`].
+    pub fn from_iter_with_policy<I: IntoIterator<Item = (K, O, V)>>(
+        iter: I,
+        policy: DuplicatePolicy<'_, K, O, V>,
+    ) -> Result<Self, OccupiedError<K, O, V>>
+    where
+        S: Default,
+    {
+        let mut map = Self::default();
+        map.extend_with_policy(iter, policy)?;
+        Ok(map)
+    }
+
+    /// Adds the order and the value returned by `f` if there is no entry with the key. Returns
+    /// references to the order and the value of the entry either way.
+    pub fn get_or_insert_with<F: FnOnce() -> (O, V)>(&mut self, key: K, f: F) -> (&O, &mut V) {
+        self.entry(key).or_insert_with(f)
+    }
+
+    fn reassign_group(&mut self, from: &O, to: O) {
+        let keys = self.ordered_keys.remove(from).unwrap();
+        for key in keys.iter() {
+            let (order, _value) = self.values.get_mut(key).unwrap();
+            *order = to.clone();
+        }
+        match self.ordered_keys.entry(to) {
+            btree_map::Entry::Occupied(mut entry) => entry.get_mut().append(keys),
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(keys);
+            }
+        }
+    }
+
+    /// Moves all entries with the order `from` to the order `to`, merging them with the entries
+    /// already there. Returns the number of entries moved.
+    pub fn reassign_order(&mut self, from: &O, to: O) -> usize {
+        let len = self.group_len(from);
+        if len != 0 {
+            self.reassign_group(from, to);
+        }
+        len
+    }
+
+    /// Returns the group of entries with the given order for in-place manipulation.
+    pub fn order_entry(&mut self, order: O) -> OrderEntry<'_, K, O, V, S, G> {
+        if self.ordered_keys.contains_key(&order) {
+            OrderEntry::Occupied(OccupiedOrderEntry { map: self, order })
+        } else {
+            OrderEntry::Vacant(VacantOrderEntry { map: self, order })
+        }
+    }
+
+    /// Returns the group with the smallest order for in-place manipulation.
+    pub fn first_entry(&mut self) -> Option<OccupiedOrderEntry<'_, K, O, V, S, G>> {
+        let order = self.min_order()?.clone();
+        Some(OccupiedOrderEntry { map: self, order })
+    }
+
+    /// Returns the group with the largest order for in-place manipulation.
+    pub fn last_entry(&mut self) -> Option<OccupiedOrderEntry<'_, K, O, V, S, G>> {
+        let order = self.max_order()?.clone();
+        Some(OccupiedOrderEntry { map: self, order })
+    }
+
+    /// Returns the entry for the given key for in-place manipulation.
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, O, V, S, G> {
+        let hasher = self.values.hasher().clone();
+        match self.values.entry(key) {
+            hash_map::Entry::Occupied(entry) => Entry::Occupied(OccupiedEntry {
+                entry,
+                ordered_keys: &mut self.ordered_keys,
+                hasher,
+            }),
+            hash_map::Entry::Vacant(entry) => Entry::Vacant(VacantEntry {
+                entry,
+                ordered_keys: &mut self.ordered_keys,
+                hasher,
+            }),
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>>
+    Extend<(K, O, V)> for Map<K, O, V, S, G>
+{
+    /// Adds all entries, overwriting the entries with the same keys.
+    fn extend<I: IntoIterator<Item = (K, O, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        let (lower, _upper) = iter.size_hint();
+        let additional = if self.is_empty() {
+            lower
+        } else {
+            lower.div_ceil(2)
+        };
+        self.values.reserve(additional);
+        for (key, order, value) in iter {
+            self.add(key, order, value);
+        }
+    }
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, S: BuildHasher + Clone, G: KeySet<K, S>>
+    Extend<(K, (O, V))> for Map<K, O, V, S, G>
+{
+    /// Adds all entries, overwriting the entries with the same keys.
+    fn extend<I: IntoIterator<Item = (K, (O, V))>>(&mut self, iter: I) {
+        self.extend(
+            iter.into_iter()
+                .map(|(key, (order, value))| (key, order, value)),
+        );
+    }
+}
+
+impl<K, O, V, S, G> FromIterator<(K, O, V)> for Map<K, O, V, S, G>
+where
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    S: BuildHasher + Clone + Default,
+    G: KeySet<K, S>,
+{
+    /// Collects the entries into a map. Later entries overwrite earlier ones with the same keys.
+    fn from_iter<I: IntoIterator<Item = (K, O, V)>>(iter: I) -> Self {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, O, V, S, G> FromIterator<(K, (O, V))> for Map<K, O, V, S, G>
+where
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    S: BuildHasher + Clone + Default,
+    G: KeySet<K, S>,
+{
+    /// Collects the entries into a map. Later entries overwrite earlier ones with the same keys.
+    fn from_iter<I: IntoIterator<Item = (K, (O, V))>>(iter: I) -> Self {
+        let mut map = Self::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V, const N: usize> From<[(K, O, V); N]>
+    for Map<K, O, V>
+{
+    /// Later entries overwrite earlier ones with the same keys.
+    fn from(entries: [(K, O, V); N]) -> Self {
+        Self::from_iter(entries)
+    }
+}
+
+impl<K, O, V, S> From<HashMap<K, (O, V), S>> for Map<K, O, V, S>
+where
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    S: BuildHasher + Clone,
+{
+    /// Keeps the hasher of the map.
+    fn from(values: HashMap<K, (O, V), S>) -> Self {
+        Self::from_values(values)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<K, O, V, S> From<std::collections::HashMap<K, (O, V), S>> for Map<K, O, V, S>
+where
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    S: BuildHasher + Clone,
+{
+    /// Keeps the hasher of the map.
+    fn from(values: std::collections::HashMap<K, (O, V), S>) -> Self {
+        let mut converted =
+            HashMap::with_capacity_and_hasher(values.len(), values.hasher().clone());
+        converted.extend(values);
+        Self::from(converted)
+    }
+}
+
+impl<K: Clone + Eq + Hash, O: Clone + Ord, V> From<BTreeMap<K, (O, V)>> for Map<K, O, V> {
+    fn from(values: BTreeMap<K, (O, V)>) -> Self {
+        Self::from_iter(values)
+    }
+}
+
+impl<K, O, V, S, G> Clone for Map<K, O, V, S, G>
+where
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    V: Clone,
+    S: BuildHasher + Clone,
+    G: KeySet<K, S> + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            ordered_keys: self.ordered_keys.clone(),
+        }
+    }
+
+    /// Reuses the allocation of the value map and the allocations of the groups whose orders are
+    /// also in `source`.
+    fn clone_from(&mut self, source: &Self) {
+        self.values.clear();
+        self.values.extend(
+            source
+                .values
+                .iter()
+                .map(|(key, entry)| (key.clone(), entry.clone())),
+        );
+        self.ordered_keys
+            .retain(|order, _keys| source.ordered_keys.contains_key(order));
+        for (order, keys) in &source.ordered_keys {
+            match self.ordered_keys.get_mut(order) {
+                Some(group) => {
+                    group.clear();
+                    for key in keys.iter() {
+                        group.insert(key.clone());
+                    }
+                }
+                None => {
+                    self.ordered_keys.insert(order.clone(), keys.clone());
+                }
+            }
+        }
+    }
+}
+
+impl<K, Q, O, V, S, G> Index<&Q> for Map<K, O, V, S, G>
+where
+    K: Borrow<Q> + Eq + Hash,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    /// Returns a reference to the value of an entry.
+    ///
+    /// Panics if there is no entry with the given key.
+    fn index(&self, key: &Q) -> &V {
+        let (_order, value) = self.values.get(key).expect("no entry found for key");
+        value
+    }
+}
+
+impl<K: Eq + Hash, O: PartialEq, V: PartialEq, S: BuildHasher, G> PartialEq for Map<K, O, V, S, G> {
+    /// Maps are equal if they associate the same keys with the same orders and values.
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values
+    }
+}
+
+impl<K: Eq + Hash, O: Eq, V: Eq, S: BuildHasher, G> Eq for Map<K, O, V, S, G> {}
+
+impl<K: Eq + Hash, O: Hash, V: Hash, S, G> Hash for Map<K, O, V, S, G> {
+    /// Hashes the entries independently of their iteration order, so equal maps hash equally.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let entry_hasher = foldhash::fast::FixedState::default();
+        let mut entries_hash: u64 = 0;
+        for entry in &self.values {
+            entries_hash = entries_hash.wrapping_add(entry_hasher.hash_one(entry));
+        }
+        state.write_usize(self.len());
+        state.write_u64(entries_hash);
+    }
+}
+
+impl<K: Debug + Eq + Hash, O: Debug, V: Debug, S: BuildHasher, G: KeySet<K, S>> Debug
+    for Map<K, O, V, S, G>
+{
+    /// Formats the entries as a map from orders to groups of keys and values.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Group<'a, K, O, V, S, G> {
+            keys: &'a G,
+            values: &'a HashMap<K, (O, V), S>,
+        }
+
+        impl<K: Debug + Eq + Hash, O, V: Debug, S: BuildHasher, G: KeySet<K, S>> Debug
+            for Group<'_, K, O, V, S, G>
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_map()
+                    .entries(self.keys.iter().map(|key| (key, &self.values[key].1)))
+                    .finish()
+            }
+        }
+
+        f.debug_map()
+            .entries(self.ordered_keys.iter().map(|(order, keys)| {
+                let group = Group {
+                    keys,
+                    values: &self.values,
+                };
+                (order, group)
+            }))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeSet;
+
+    use super::*;
+
+    type HashMap<K, V> = super::HashMap<K, V, DefaultHashBuilder>;
+    type HashSet<K> = super::HashSet<K, DefaultHashBuilder>;
+
+    fn sort<O, T: Ord, F: FnOnce(&mut O) -> &mut Vec<T>>(mut value: O, grabber: F) -> O {
+        grabber(&mut value).sort();
+        value
+    }
+
+    fn maybe_sort<O, T: Ord, F: FnOnce(&mut O) -> &mut Vec<T>>(
+        mut value: Option<O>,
+        grabber: F,
+    ) -> Option<O> {
+        if let Some(value) = &mut value {
+            grabber(value).sort();
+        }
+        value
+    }
+
+    #[test]
+    fn len_and_contains_key() {
+        let mut map = Map::new();
+        assert!(map.is_empty());
+        assert_eq!(map.len(), 0);
+        assert!(!map.contains_key(&1));
+
+        map.add(1, 10, "a");
+        map.add(2, 10, "b");
+        map.add(1, 20, "c");
+        assert!(!map.is_empty());
+        assert_eq!(map.len(), 2);
+        assert!(map.contains_key(&1));
+        assert!(!map.contains_key(&3));
+
+        map.remove(&1);
+        assert_eq!(map.len(), 1);
+        assert!(!map.contains_key(&1));
+    }
+
+    #[test]
+    fn get_and_get_mut() {
+        let mut map = Map::new();
+        map.add(1, 10, "a");
+        assert_eq!(map.get(&1), Some((&10, &"a")));
+        assert_eq!(map.get(&2), None);
+
+        let (order, value) = map.get_mut(&1).unwrap();
+        assert_eq!(order, &10);
+        *value = "b";
+        assert_eq!(map.get(&1), Some((&10, &"b")));
+        assert_eq!(map.get_mut(&2), None);
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(10, HashSet::from_iter([1]))])
+        );
+    }
+
+    #[test]
+    fn value_mutation() {
+        let mut map = Map::new();
+        map.add(1, 10, 100);
+        *map.get_value_mut(&1).unwrap() += 1;
+        assert!(map.update_value(&1, |value| *value *= 2));
+        assert!(!map.update_value(&2, |_| unreachable!()));
+        assert_eq!(map.get_value_mut(&2), None);
+        assert_eq!(map.values, HashMap::from_iter([(1, (10, 202))]));
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(10, HashSet::from_iter([1]))])
+        );
+    }
+
+    #[test]
+    fn clear() {
+        let mut map = Map::new();
+        for i in 0..100 {
+            map.add(i, i % 7, ());
+        }
+        let capacity = map.values.capacity();
+        map.clear();
+        assert!(map.is_empty());
+        assert!(map.ordered_keys.is_empty());
+        assert_eq!(map.values.capacity(), capacity);
+        assert_eq!(map.peek_smallest(), None);
+    }
+
+    #[test]
+    fn largest() {
+        let mut map = Map::new();
+        assert_eq!(map.peek_largest(), None);
+        assert_eq!(map.remove_largest(), None);
+
+        map.add(1, 10, "a");
+        map.add(2, 30, "b");
+        map.add(3, 30, "c");
+        map.add(4, 20, "d");
+
+        assert_eq!(
+            maybe_sort(map.peek_largest(), |r| &mut r.1),
+            Some((&30, vec![(&2, &"b"), (&3, &"c")]))
+        );
+        assert_eq!(
+            maybe_sort(map.remove_largest(), |r| &mut r.1),
+            Some((30, vec![(2, "b"), (3, "c")]))
+        );
+        assert_eq!(map.remove_largest(), Some((20, vec![(4, "d")])));
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(10, HashSet::from_iter([1]))])
+        );
+        assert_eq!(map.values, HashMap::from_iter([(1, (10, "a"))]));
+    }
+
+    #[test]
+    fn iter() {
+        let mut map = Map::new();
+        assert_eq!(map.iter().next(), None);
+
+        map.add(1, 20, "a");
+        map.add(2, 10, "b");
+        map.add(3, 30, "c");
+        map.add(4, 10, "d");
+
+        let mut entries: Vec<_> = map.iter().collect();
+        entries[..2].sort();
+        assert_eq!(
+            entries,
+            vec![
+                (&2, &10, &"b"),
+                (&4, &10, &"d"),
+                (&1, &20, &"a"),
+                (&3, &30, &"c")
+            ]
+        );
+        assert_eq!((&map).into_iter().count(), 4);
+    }
+
+    #[test]
+    fn iter_mut() {
+        let mut map = Map::new();
+        assert_eq!(map.iter_mut().next(), None);
+
+        map.add(1, 20, 1);
+        map.add(2, 10, 2);
+        map.add(3, 30, 3);
+
+        let mut orders = Vec::new();
+        for (key, order, value) in &mut map {
+            *value += key * 10;
+            orders.push(*order);
+        }
+        assert_eq!(orders, vec![10, 20, 30]);
+        assert_eq!(
+            map.values,
+            HashMap::from_iter([(1, (20, 11)), (2, (10, 22)), (3, (30, 33))])
+        );
+
+        let mut iter = map.iter_mut();
+        let (_key, _order, last) = iter.next_back().unwrap();
+        let values: Vec<_> = iter.map(|(_key, _order, value)| value).collect();
+        *last = 0;
+        for value in values {
+            *value = 0;
+        }
+        assert!(map.values().all(|value| *value == 0));
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut map = Map::new();
+        map.add(1, 20, "a");
+        map.add(2, 10, "b");
+        map.add(3, 30, "c");
+        map.add(4, 10, "d");
+
+        let mut entries: Vec<_> = map.into_iter().collect();
+        entries[..2].sort();
+        assert_eq!(
+            entries,
+            vec![(2, 10, "b"), (4, 10, "d"), (1, 20, "a"), (3, 30, "c")]
+        );
+        assert_eq!(Map::<i32, i32, ()>::new().into_iter().next(), None);
+    }
+
+    #[test]
+    fn double_ended_iteration() {
+        let mut map = Map::new();
+        map.add(1, 20, "a");
+        map.add(2, 10, "b");
+        map.add(3, 30, "c");
+        map.add(4, 10, "d");
+
+        let orders: Vec<_> = map.iter().rev().map(|(_, order, _)| *order).collect();
+        assert_eq!(orders, vec![30, 20, 10, 10]);
+
+        let mut iter = map.iter();
+        assert_eq!(iter.len(), 4);
+        assert_eq!(iter.next_back(), Some((&3, &30, &"c")));
+        assert_eq!(iter.next_back(), Some((&1, &20, &"a")));
+        assert_eq!(iter.len(), 2);
+        let mut rest = vec![iter.next().unwrap(), iter.next_back().unwrap()];
+        rest.sort();
+        assert_eq!(rest, vec![(&2, &10, &"b"), (&4, &10, &"d")]);
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        let mut iter_mut = map.iter_mut();
+        assert_eq!(iter_mut.len(), 4);
+        assert_eq!(iter_mut.next_back(), Some((&3, &30, &mut "c")));
+
+        let mut into_iter = map.into_iter();
+        assert_eq!(into_iter.next_back(), Some((3, 30, "c")));
+        let mut smallest = vec![into_iter.next().unwrap(), into_iter.next().unwrap()];
+        smallest.sort();
+        assert_eq!(smallest, vec![(2, 10, "b"), (4, 10, "d")]);
+        assert_eq!(into_iter.len(), 1);
+        assert_eq!(into_iter.next_back(), Some((1, 20, "a")));
+        assert_eq!(into_iter.next(), None);
+    }
+
+    #[test]
+    fn views() {
+        let mut map = Map::new();
+        map.add(1, 20, "a");
+        map.add(2, 10, "b");
+        map.add(3, 30, "c");
+        map.add(4, 10, "d");
+
+        let mut keys: Vec<_> = map.keys().collect();
+        keys[..2].sort();
+        assert_eq!(keys, vec![&2, &4, &1, &3]);
+        assert_eq!(map.keys().len(), 4);
+
+        let mut values: Vec<_> = map.values().collect();
+        values[..2].sort();
+        assert_eq!(values, vec![&"b", &"d", &"a", &"c"]);
+        assert_eq!(map.values().next_back(), Some(&"c"));
+
+        assert_eq!(map.orders().collect::<Vec<_>>(), vec![&10, &20, &30]);
+        assert_eq!(map.orders().next_back(), Some(&30));
+        assert_eq!(map.orders().len(), 3);
+    }
+
+    #[test]
+    fn unordered_iteration() {
+        let mut map = Map::new();
+        map.add(1, 20, 1);
+        map.add(2, 10, 2);
+        map.add(3, 10, 3);
+
+        let mut entries: Vec<_> = map.iter_unordered().collect();
+        entries.sort();
+        assert_eq!(entries, vec![(&1, &20, &1), (&2, &10, &2), (&3, &10, &3)]);
+        assert_eq!(map.iter_unordered().len(), 3);
+
+        for (key, order, value) in map.iter_mut_unordered() {
+            *value += key * order;
+        }
+        for value in map.values_mut_unordered() {
+            *value *= 10;
+        }
+        assert_eq!(
+            map.values,
+            HashMap::from_iter([(1, (20, 210)), (2, (10, 220)), (3, (10, 330))])
+        );
+    }
+
+    #[test]
+    fn entry() {
+        let mut map = Map::new();
+
+        let (order, value) = map.entry(1).or_insert(10, 100);
+        assert_eq!(order, &10);
+        *value += 1;
+        assert_eq!(
+            map.entry(1).or_insert_with(|| unreachable!()),
+            (&10, &mut 101)
+        );
+        map.entry(1).and_modify(|value| *value += 1);
+        map.entry(2)
+            .and_modify(|_| unreachable!())
+            .or_insert(10, 200);
+        assert_eq!(map.entry(3).key(), &3);
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(10, HashSet::from_iter([1, 2]))])
+        );
+        assert_eq!(
+            map.values,
+            HashMap::from_iter([(1, (10, 102)), (2, (10, 200))])
+        );
+
+        let Entry::Occupied(mut entry) = map.entry(1) else {
+            panic!("the entry should be occupied");
+        };
+        assert_eq!(entry.get(), (&10, &102));
+        *entry.get_mut().1 += 1;
+        assert_eq!(entry.set_order(20), 10);
+        assert_eq!(entry.set_order(20), 20);
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(10, HashSet::from_iter([2])), (20, HashSet::from_iter([1]))])
+        );
+        assert_eq!(
+            map.values,
+            HashMap::from_iter([(1, (20, 103)), (2, (10, 200))])
+        );
+
+        let Entry::Occupied(entry) = map.entry(2) else {
+            panic!("the entry should be occupied");
+        };
+        assert_eq!(entry.remove_entry(), (2, 10, 200));
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(20, HashSet::from_iter([1]))])
+        );
+        assert_eq!(map.values, HashMap::from_iter([(1, (20, 103))]));
+
+        let Entry::Vacant(entry) = map.entry(2) else {
+            panic!("the entry should be vacant");
+        };
+        assert_eq!(entry.insert(20, 0), (&20, &mut 0));
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(20, HashSet::from_iter([1, 2]))])
+        );
+    }
+
+    #[test]
+    fn order_entry() {
+        let mut map = Map::new();
+        map.add(1, 10, "a");
+        map.add(2, 20, "b");
+
+        let OrderEntry::Vacant(entry) = map.order_entry(30) else {
+            panic!("the group should be vacant");
+        };
+        assert_eq!(entry.order(), &30);
+        let (old_entry, mut entry) = entry.add(3, "c");
+        assert_eq!(old_entry, None);
+        assert_eq!(entry.add(1, "d"), Some((10, "a")));
+        assert_eq!(entry.len(), 2);
+        let mut group: Vec<_> = entry.iter().collect();
+        group.sort();
+        assert_eq!(group, vec![(&1, &"d"), (&3, &"c")]);
+
+        assert_eq!(entry.set_order(20), 30);
+        assert_eq!(entry.order(), &20);
+        assert_eq!(entry.len(), 3);
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(20, HashSet::from_iter([1, 2, 3]))])
+        );
+        assert_eq!(
+            map.values,
+            HashMap::from_iter([(1, (20, "d")), (2, (20, "b")), (3, (20, "c"))])
+        );
+
+        map.add(4, 40, "e");
+        let OrderEntry::Occupied(entry) = map.order_entry(20) else {
+            panic!("the group should be occupied");
+        };
+        let mut drain = entry.drain();
+        assert_eq!(drain.len(), 3);
+        assert!(drain.next().is_some());
+        drop(drain);
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(40, HashSet::from_iter([4]))])
+        );
+        assert_eq!(map.values, HashMap::from_iter([(4, (40, "e"))]));
+    }
+
+    #[test]
+    fn retain() {
+        let mut map = Map::new();
+        for i in 0..10 {
+            map.add(i, i % 3, i);
+        }
+        map.retain(|key, order, value| {
+            *value *= 10;
+            *order != 1 && key % 2 == 0
+        });
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([
+                (0, HashSet::from_iter([0, 6])),
+                (2, HashSet::from_iter([2, 8]))
+            ])
+        );
+        assert_eq!(
+            map.values,
+            HashMap::from_iter([(0, (0, 0)), (6, (0, 60)), (2, (2, 20)), (8, (2, 80))])
+        );
+    }
+
+    #[test]
+    fn extract_if() {
+        let mut map = Map::new();
+        for i in 0..10 {
+            map.add(i, 10 - i, i);
+        }
+        let extracted: Vec<_> = map.extract_if(|key, _order, _value| key % 3 == 0).collect();
+        assert_eq!(extracted, vec![(9, 1, 9), (6, 4, 6), (3, 7, 3), (0, 10, 0)]);
+        assert_eq!(map.len(), 6);
+        assert!(!map.contains_key(&3));
+
+        let mut extract = map.extract_if(|_key, order, _value| *order > 4);
+        assert_eq!(extract.next(), Some((5, 5, 5)));
+        drop(extract);
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![8, 7, 4, 2, 1]);
+        assert_eq!(map.ordered_keys.len(), 5);
+    }
+
+    #[test]
+    fn drain() {
+        let mut map = Map::new();
+        for i in 0..100 {
+            map.add(i, 100 - i, ());
+        }
+        let drained: Vec<_> = map.drain().map(|(key, _order, _value)| key).collect();
+        assert_eq!(drained, (0..100).rev().collect::<Vec<_>>());
+        assert!(map.is_empty());
+        assert!(map.ordered_keys.is_empty());
+
+        map.add(1, 10, ());
+        map.add(2, 20, ());
+        let mut drain = map.drain();
+        assert_eq!(drain.len(), 2);
+        assert_eq!(drain.next_back(), Some((2, 20, ())));
+        drop(drain);
+        assert!(map.values.is_empty());
+        assert!(map.ordered_keys.is_empty());
+
+        let capacity = map.capacity();
+        map.add(3, 30, ());
+        map.add(4, 40, ());
+        assert_eq!(map.drain().next(), Some((3, 30, ())));
+        assert!(map.values.is_empty());
+        assert!(map.ordered_keys.is_empty());
+        assert_eq!(map.capacity(), capacity);
+        map.add(5, 50, ());
+        assert_eq!(map.remove_smallest(), Some((50, vec![(5, ())])));
+    }
+
+    #[test]
+    fn range() {
+        let mut map = Map::new();
+        for i in 0..10 {
+            map.add(i, i * 10, i);
+        }
+        map.add(10, 30, 10);
+
+        let mut entries: Vec<_> = map.range(20..=40).collect();
+        entries[1..3].sort();
+        assert_eq!(
+            entries,
+            vec![(&2, &20, &2), (&3, &30, &3), (&10, &30, &10), (&4, &40, &4)]
+        );
+        assert_eq!(map.range(20..40).len(), 3);
+        assert_eq!(map.range(..).next_back(), Some((&9, &90, &9)));
+        assert_eq!(map.range(100..).next(), None);
+
+        for (_key, order, value) in map.range_mut(80..) {
+            *value += order;
+        }
+        assert_eq!(map.range_mut(..10).next(), Some((&0, &0, &mut 0)));
+        assert_eq!(map.range_mut(80..).len(), 2);
+        assert_eq!(map.values().copied().collect::<Vec<_>>()[9..], vec![88, 99]);
+
+        let values: Vec<_> = map
+            .range_mut(30..=30)
+            .map(|(_key, _order, value)| value)
+            .collect();
+        for value in values {
+            *value = 0;
+        }
+        assert!(map.range(30..=30).all(|(_key, _order, value)| *value == 0));
+    }
+
+    #[test]
+    fn drain_range() {
+        let mut map = Map::new();
+        for i in 0..10 {
+            map.add(i, i * 10, i);
+        }
+        map.add(10, 30, 10);
+
+        let mut drained: Vec<_> = map.drain_range(20..40).collect();
+        drained[1..].sort();
+        assert_eq!(drained, vec![(2, 20, 2), (3, 30, 3), (10, 30, 10)]);
+        assert_eq!(map.len(), 8);
+        assert_eq!(
+            map.orders().copied().collect::<Vec<_>>(),
+            vec![0, 10, 40, 50, 60, 70, 80, 90]
+        );
+
+        let mut drain = map.drain_range(50..=70);
+        assert_eq!(drain.len(), 3);
+        assert_eq!(drain.next_back(), Some((7, 70, 7)));
+        drop(drain);
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![0, 1, 4, 8, 9]);
+        assert_eq!(map.values.len(), 5);
+        assert_eq!(map.drain_range(100..).next(), None);
+
+        core::mem::forget(map.drain_range(..5));
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![1, 4, 8, 9]);
+        assert_eq!(map.values.len(), 4);
+    }
+
+    #[test]
+    fn split_off() {
+        let mut map = Map::new();
+        for i in 0..10 {
+            map.add(i, i % 5, i);
+        }
+
+        let upper = map.split_off(&3);
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([
+                (0, HashSet::from_iter([0, 5])),
+                (1, HashSet::from_iter([1, 6])),
+                (2, HashSet::from_iter([2, 7]))
+            ])
+        );
+        assert_eq!(map.values.len(), 6);
+        assert_eq!(
+            upper.ordered_keys,
+            BTreeMap::from([
+                (3, HashSet::from_iter([3, 8])),
+                (4, HashSet::from_iter([4, 9]))
+            ])
+        );
+        assert_eq!(
+            upper.values,
+            HashMap::from_iter([(3, (3, 3)), (8, (3, 8)), (4, (4, 4)), (9, (4, 9))])
+        );
+        assert!(map.split_off(&10).is_empty());
+    }
+
+    #[test]
+    fn append() {
+        let mut map = Map::new();
+        map.add(1, 10, "a");
+        map.add(2, 20, "b");
+        let mut other = Map::new();
+        other.add(2, 30, "c");
+        other.add(3, 10, "d");
+
+        map.append(&mut other);
+        assert!(other.is_empty());
+        assert!(other.ordered_keys.is_empty());
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([
+                (10, HashSet::from_iter([1, 3])),
+                (30, HashSet::from_iter([2]))
+            ])
+        );
+        assert_eq!(
+            map.values,
+            HashMap::from_iter([(1, (10, "a")), (2, (30, "c")), (3, (10, "d"))])
+        );
+
+        let mut empty = Map::new();
+        empty.append(&mut map);
+        assert!(map.is_empty());
+        assert_eq!(empty.len(), 3);
+    }
+
+    #[test]
+    fn merge_with() {
+        let mut map = Map::new();
+        map.add(1, 10, 1);
+        map.add(2, 20, 2);
+        let mut other = Map::new();
+        other.add(2, 15, 3);
+        other.add(3, 30, 4);
+
+        map.merge_with(other, |key, (o1, v1), (o2, v2)| {
+            assert_eq!(key, &2);
+            (o1.min(o2), v1 + v2)
+        });
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([
+                (10, HashSet::from_iter([1])),
+                (15, HashSet::from_iter([2])),
+                (30, HashSet::from_iter([3]))
+            ])
+        );
+        assert_eq!(
+            map.values,
+            HashMap::from_iter([(1, (10, 1)), (2, (15, 5)), (3, (30, 4))])
+        );
+    }
+
+    #[test]
+    fn from_iter_and_extend() {
+        let mut map: Map<_, _, _> = [(1, 10, "a"), (2, 20, "b"), (1, 30, "c")]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(20, HashSet::from_iter([2])), (30, HashSet::from_iter([1]))])
+        );
+        assert_eq!(
+            map.values,
+            HashMap::from_iter([(1, (30, "c")), (2, (20, "b"))])
+        );
+
+        map.extend([(3, (10, "d")), (2, (10, "e"))]);
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([
+                (10, HashSet::from_iter([2, 3])),
+                (30, HashSet::from_iter([1]))
+            ])
+        );
+
+        let map: Map<_, _, _> = [(1, (10, "a"))].into_iter().collect();
+        assert_eq!(map.values, HashMap::from_iter([(1, (10, "a"))]));
+    }
+
+    #[test]
+    fn from_collections() {
+        let expected_ordered_keys = BTreeMap::from([
+            (10, HashSet::from_iter([1, 3])),
+            (20, HashSet::from_iter([2])),
+        ]);
+        let expected_values = HashMap::from_iter([(1, (10, "a")), (2, (20, "b")), (3, (10, "c"))]);
+
+        let map = Map::from([(1, 10, "a"), (2, 20, "b"), (3, 10, "c")]);
+        assert_eq!(map.ordered_keys, expected_ordered_keys);
+        assert_eq!(map.values, expected_values);
+
+        let map = Map::from(expected_values.clone());
+        assert_eq!(map.ordered_keys, expected_ordered_keys);
+        assert_eq!(map.values, expected_values);
+
+        let map = Map::from(BTreeMap::from_iter(expected_values.clone()));
+        assert_eq!(map.ordered_keys, expected_ordered_keys);
+        assert_eq!(map.values, expected_values);
+    }
+
+    #[test]
+    fn into_keys_and_values() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 30, "c")]);
+        assert_eq!(map.into_keys().collect::<Vec<_>>(), vec![2, 1, 3]);
+
+        let map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 30, "c")]);
+        let mut values = map.into_values();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values.next_back(), Some("c"));
+        assert_eq!(values.collect::<Vec<_>>(), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn debug() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "b")]);
+        assert_eq!(format!("{map:?}"), r#"{10: {2: "b"}, 20: {1: "a"}}"#);
+        assert_eq!(
+            format!("{map:#?}"),
+            r#"{
+    10: {
+        2: "b",
+    },
+    20: {
+        1: "a",
+    },
+}"#
+        );
+        assert_eq!(format!("{:?}", Map::<i32, i32, i32>::new()), "{}");
+    }
+
+    #[test]
+    fn clone() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "b")]);
+        let cloned = map.clone();
+        assert_eq!(cloned.ordered_keys, map.ordered_keys);
+        assert_eq!(cloned.values, map.values);
+
+        let mut target = Map::new();
+        for i in 0..100 {
+            target.add(i, i % 2 * 10, "c");
+        }
+        let capacity = target.values.capacity();
+        let group_capacity = target.ordered_keys[&10].capacity();
+        target.clone_from(&map);
+        assert_eq!(target.ordered_keys, map.ordered_keys);
+        assert_eq!(target.values, map.values);
+        assert_eq!(target.values.capacity(), capacity);
+        assert_eq!(target.ordered_keys[&10].capacity(), group_capacity);
+    }
+
+    #[test]
+    fn default() {
+        #[derive(Default)]
+        struct Scheduler {
+            queue: Map<u32, u64, String>,
+        }
+
+        let scheduler = Scheduler::default();
+        assert!(scheduler.queue.is_empty());
+        assert_eq!(scheduler.queue.values.capacity(), 0);
+    }
+
+    #[test]
+    fn eq() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 10, "c")]);
+        let mut other = Map::from([(3, 10, "c"), (2, 10, "b")]);
+        assert_ne!(map, other);
+        other.add(1, 20, "a");
+        assert_eq!(map, other);
+        other.add(1, 30, "a");
+        assert_ne!(map, other);
+        other.add(1, 20, "d");
+        assert_ne!(map, other);
+    }
+
+    #[test]
+    fn index() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "b")]);
+        assert_eq!(map[&1], "a");
+        assert_eq!(map[&2], "b");
+    }
+
+    #[test]
+    #[should_panic(expected = "no entry found for key")]
+    fn index_missing_key() {
+        let map = Map::from([(1, 20, "a")]);
+        let _ = map[&2];
+    }
+
+    #[test]
+    fn hash() {
+        fn hash_of(map: &Map<i32, i32, &str>) -> u64 {
+            let mut hasher = std::hash::DefaultHasher::new();
+            map.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        let mut map = Map::new();
+        let mut other = Map::new();
+        for i in 0..100 {
+            map.add(i, i % 7, "a");
+            other.add(99 - i, (99 - i) % 7, "a");
+        }
+        assert_eq!(hash_of(&map), hash_of(&other));
+        other.add(0, 1, "a");
+        assert_ne!(hash_of(&map), hash_of(&other));
+        assert_ne!(hash_of(&map), hash_of(&Map::new()));
+
+        let maps = std::collections::HashSet::from([map.clone(), map]);
+        assert_eq!(maps.len(), 1);
+    }
+
+    #[test]
+    fn custom_hasher() {
+        type Seeded = core::hash::BuildHasherDefault<std::hash::DefaultHasher>;
+
+        let mut map: Map<i32, i32, &str, Seeded> = Map::with_hasher(Seeded::new());
+        map.add(1, 10, "a");
+        map.add(2, 10, "b");
+        map.entry(3).or_insert(20, "c");
+        if let Entry::Occupied(mut entry) = map.entry(1) {
+            entry.set_order(30);
+        }
+        assert_eq!(map.orders().copied().collect::<Vec<_>>(), vec![10, 20, 30]);
+        assert_eq!(map.split_off(&20).len(), 2);
+        assert_eq!(map.clone(), map);
+
+        const EMPTY: Map<i32, i32, &str, Seeded> = Map::with_hasher(Seeded::new());
+        let map = Map::with_capacity_and_hasher(10, Seeded::new());
+        assert!(map.values.capacity() >= 10);
+        assert_eq!(map, EMPTY);
+    }
+
+    #[test]
+    #[cfg(any(feature = "ahash", feature = "fxhash"))]
+    fn fast_default_hasher() {
+        let map: Map<i32, i32, &str> = Map::from([(1, 10, "a"), (2, 10, "b")]);
+        #[cfg(feature = "fxhash")]
+        let _: &rustc_hash::FxBuildHasher = map.hasher();
+        #[cfg(not(feature = "fxhash"))]
+        let _: &ahash::RandomState = map.hasher();
+        assert_eq!(map.ordered_keys[&10].len(), 2);
+    }
+
+    #[test]
+    #[cfg(feature = "nohash")]
+    fn int_map() {
+        let mut map: IntMap<u64, u32, &str> = IntMap::default();
+        map.add(7, 2, "a");
+        map.add(3, 1, "b");
+        map.add(7, 1, "c");
+        assert_eq!(map.keys().count(), 2);
+        assert_eq!(map.peek_smallest().map(|(_, group)| group.len()), Some(2));
+        assert_eq!(map.remove(&7), Some((1, "c")));
+    }
+
+    #[test]
+    fn capacity() {
+        let mut map: Map<i32, i32, i32> = Map::with_capacity(100);
+        assert!(map.capacity() >= 100);
+        map.extend((0..100).map(|i| (i, i % 2, i)));
+        map.retain(|key, _order, _value| *key < 4);
+        map.shrink_to_fit();
+        assert!(map.capacity() < 100);
+        assert!(map.ordered_keys.values().all(|keys| keys.capacity() < 50));
+        map.reserve(1000);
+        assert!(map.capacity() >= 1004);
+        assert!(map.try_reserve(2000).is_ok());
+        assert!(map.capacity() >= 2004);
+        assert!(map.try_reserve(usize::MAX).is_err());
+        assert_eq!(map.len(), 4);
+    }
+
+    #[test]
+    fn drain_smallest() {
+        let mut map = Map::from([(1, 10, "a"), (2, 10, "b"), (3, 10, "c"), (4, 20, "d")]);
+        let (order, mut drain) = map.drain_smallest().unwrap();
+        assert_eq!(order, 10);
+        assert_eq!(drain.len(), 3);
+        assert!(drain.next().is_some());
+        drop(drain);
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(20, HashSet::from_iter([4]))])
+        );
+        assert_eq!(map.values, HashMap::from_iter([(4, (20, "d"))]));
+
+        let (order, drain) = map.drain_smallest().unwrap();
+        assert_eq!((order, drain.collect::<Vec<_>>()), (20, vec![(4, "d")]));
+        assert!(map.is_empty());
+        assert!(map.drain_smallest().is_none());
+
+        #[derive(Clone)]
+        struct Counted(i32, std::rc::Rc<core::cell::Cell<usize>>);
+
+        impl PartialEq for Counted {
+            fn eq(&self, other: &Self) -> bool {
+                self.0 == other.0
+            }
+        }
+
+        impl Eq for Counted {}
+
+        impl Hash for Counted {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.1.set(self.1.get() + 1);
+                self.0.hash(state);
+            }
+        }
+
+        let hashes = std::rc::Rc::new(core::cell::Cell::new(0));
+        let mut map = Map::new();
+        for i in 0..100 {
+            map.add(Counted(i, hashes.clone()), 10, i);
+        }
+        map.add(Counted(100, hashes.clone()), 20, 100);
+        hashes.set(0);
+        let (_order, mut drain) = map.drain_smallest().unwrap();
+        assert_eq!(drain.len(), 100);
+        assert!(drain.next().is_some());
+        assert_eq!(hashes.get(), 1);
+        drop(drain);
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.values.len(), 1);
+    }
+
+    #[test]
+    fn peek_smallest_iter() {
+        let mut map = Map::new();
+        assert!(map.peek_smallest_iter().is_none());
+        map.extend([(1, 10, "a"), (2, 10, "b"), (3, 20, "c")]);
+        let (order, iter) = map.peek_smallest_iter().unwrap();
+        assert_eq!(order, &10);
+        assert_eq!(iter.len(), 2);
+        let mut group: Vec<_> = iter.collect();
+        group.sort();
+        assert_eq!(group, vec![(&1, &"a"), (&2, &"b")]);
+    }
+
+    #[test]
+    fn pop_one_smallest() {
+        let mut map = Map::from([(1, 10, "a"), (2, 10, "b"), (3, 20, "c")]);
+        let mut popped = vec![map.pop_one_smallest().unwrap()];
+        assert_eq!(map.ordered_keys[&10].len(), 1);
+        popped.push(map.pop_one_smallest().unwrap());
+        popped.sort();
+        assert_eq!(popped, vec![(1, 10, "a"), (2, 10, "b")]);
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(20, HashSet::from_iter([3]))])
+        );
+        assert_eq!(map.pop_one_smallest(), Some((3, 20, "c")));
+        assert_eq!(map.pop_one_smallest(), None);
+        assert!(map.ordered_keys.is_empty());
+    }
+
+    #[test]
+    fn smallest_into() {
+        let mut map = Map::from([(1, 10, "a"), (2, 10, "b"), (3, 20, "c")]);
+        let mut peeked = Vec::new();
+        assert_eq!(map.peek_smallest_into(&mut peeked), Some(&10));
+        peeked.sort();
+        assert_eq!(peeked, vec![(&1, &"a"), (&2, &"b")]);
+
+        let mut group = vec![(0, "z")];
+        assert_eq!(map.remove_smallest_into(&mut group), Some(10));
+        assert_eq!(map.remove_smallest_into(&mut group), Some(20));
+        assert_eq!(map.remove_smallest_into(&mut group), None);
+        group[1..3].sort();
+        assert_eq!(group, vec![(0, "z"), (1, "a"), (2, "b"), (3, "c")]);
+        assert!(map.values.is_empty());
+    }
+
+    #[test]
+    fn remove_smallest_n() {
+        let mut map: Map<i32, i32, i32> = (0..10).map(|i| (i, i / 3, i)).collect();
+        let entries = map.remove_smallest_n(4);
+        let mut keys: Vec<_> = entries.iter().map(|(key, _order, _value)| *key).collect();
+        keys[..3].sort();
+        assert_eq!(&keys[..3], &[0, 1, 2]);
+        assert!((3..6).contains(&keys[3]));
+        assert_eq!(map.ordered_keys[&1].len(), 2);
+        assert_eq!(map.remove_smallest_n(0), vec![]);
+        assert_eq!(map.remove_smallest_n(2).len(), 2);
+        assert_eq!(map.ordered_keys.first_key_value().unwrap().0, &2);
+        assert_eq!(map.remove_smallest_n(100).len(), 4);
+        assert!(map.is_empty() && map.ordered_keys.is_empty());
+    }
+
+    #[test]
+    fn remove_smallest_while() {
+        let mut map: Map<i32, i32, i32> = (0..10).map(|i| (i, i / 2, i * 10)).collect();
+        let mut due = map.remove_smallest_while(|_key, order, _value| *order <= 2);
+        due.sort();
+        assert_eq!(
+            due,
+            vec![
+                (0, 0, 0),
+                (1, 0, 10),
+                (2, 1, 20),
+                (3, 1, 30),
+                (4, 2, 40),
+                (5, 2, 50)
+            ]
+        );
+        assert_eq!(map.ordered_keys.keys().collect::<Vec<_>>(), vec![&3, &4]);
+        assert_eq!(map.remove_smallest_while(|_, _, value| *value < 0), vec![]);
+        assert_eq!(map.remove_smallest_while(|_, _, _| true).len(), 4);
+        assert!(map.is_empty() && map.ordered_keys.is_empty());
+    }
+
+    #[test]
+    fn drain_up_to() {
+        let mut map: Map<i32, i32, i32> = (0..10).map(|i| (i, i / 2, i)).collect();
+        let below: Vec<_> = map.drain_below(&1).collect();
+        assert_eq!(sort(below, |below| below), vec![(0, 0, 0), (1, 0, 1)]);
+        let due: Vec<_> = map.drain_up_to(&2).map(|(key, _, _)| key).collect();
+        assert_eq!(sort(due, |due| due), vec![2, 3, 4, 5]);
+        assert_eq!(map.orders().collect::<Vec<_>>(), vec![&3, &4]);
+        assert_eq!(map.drain_up_to(&2).len(), 0);
+        assert_eq!(map.len(), 4);
+    }
+
+    #[test]
+    fn min_max_order() {
+        let mut map = Map::new();
+        assert_eq!((map.min_order(), map.max_order()), (None, None));
+        map.extend([(1, 20, "a"), (2, 10, "b"), (3, 30, "c"), (4, 10, "d")]);
+        assert_eq!((map.min_order(), map.max_order()), (Some(&10), Some(&30)));
+    }
+
+    #[test]
+    fn nth_smallest() {
+        let map: Map<i32, i32, i32> = (0..9).map(|i| (i, i / 3 * 10, -i)).collect();
+        assert_eq!(map.nth_smallest_order(0), Some(&0));
+        assert_eq!(map.nth_smallest_order(2), Some(&20));
+        assert_eq!(map.nth_smallest_order(3), None);
+        let (key, order, value) = map.nth_smallest_entry(4).unwrap();
+        assert_eq!(*order, 10);
+        assert!((3..6).contains(key));
+        assert_eq!(*value, -key);
+        assert_eq!(
+            map.nth_smallest_entry(8).map(|(_, order, _)| *order),
+            Some(20)
+        );
+        assert_eq!(map.nth_smallest_entry(9), None);
+    }
+
+    #[test]
+    fn rank() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 30, "c"), (4, 10, "d")]);
+        assert_eq!(map.rank(&2), Some(0));
+        assert_eq!(map.rank(&4), Some(0));
+        assert_eq!(map.rank(&1), Some(2));
+        assert_eq!(map.rank(&3), Some(3));
+        assert_eq!(map.rank(&5), None);
+    }
+
+    #[test]
+    fn count_in_range() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 30, "c"), (4, 10, "d")]);
+        assert_eq!(map.count_below(&10), 0);
+        assert_eq!(map.count_below(&25), 3);
+        assert_eq!(map.count_in_range(10..30), 3);
+        assert_eq!(map.count_in_range(15..=30), 2);
+        assert_eq!(map.count_in_range(..), 4);
+    }
+
+    #[test]
+    fn group_len_and_count() {
+        let mut map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 30, "c"), (4, 10, "d")]);
+        assert_eq!(map.group_count(), 3);
+        assert_eq!(map.group_len(&10), 2);
+        assert_eq!(map.group_len(&15), 0);
+        map.remove(&3);
+        assert_eq!(map.group_count(), 2);
+    }
+
+    #[test]
+    fn order_distribution() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 30, "c"), (4, 10, "d")]);
+        assert_eq!(
+            map.order_distribution().collect::<Vec<_>>(),
+            [(&10, 2), (&20, 1), (&30, 1)]
+        );
+        assert_eq!(map.order_distribution().next_back(), Some((&30, 1)));
+    }
+
+    #[test]
+    fn percentile_order() {
+        let mut map: Map<u32, u32, ()> = (1..=100).map(|i| (i, i, ())).collect();
+        assert_eq!(map.percentile_order(0.0), Some(&1));
+        assert_eq!(map.percentile_order(99.0), Some(&99));
+        assert_eq!(map.percentile_order(99.5), Some(&100));
+        assert_eq!(map.percentile_order(100.0), Some(&100));
+        assert_eq!(map.median_order(), Some(&50));
+        map.retain(|_, order, _| *order > 97);
+        assert_eq!(map.median_order(), Some(&99));
+        map.clear();
+        assert_eq!(map.median_order(), None);
+    }
+
+    #[test]
+    fn keys_with_order() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 30, "c"), (4, 10, "d")]);
+        let group: Vec<_> = map.keys_with_order(&10).unwrap().collect();
+        assert_eq!(sort(group, |v| v), [(&2, &"b"), (&4, &"d")]);
+        assert_eq!(map.keys_with_order(&20).unwrap().len(), 1);
+        assert!(map.keys_with_order(&15).is_none());
+    }
+
+    #[test]
+    fn contains_order() {
+        let mut map = Map::from([(1, 20, "a"), (2, 10, "b")]);
+        assert!(map.contains_order(&10));
+        assert!(!map.contains_order(&15));
+        map.remove(&2);
+        assert!(!map.contains_order(&10));
+    }
+
+    #[test]
+    fn get_order() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "b")]);
+        assert_eq!(map.get_order(&1), Some(&20));
+        assert_eq!(map.get_order(&3), None);
+    }
+
+    #[test]
+    fn set_order() {
+        let mut map = Map::from([(1, 20, "a"), (2, 10, "b")]);
+        assert_eq!(map.set_order(&1, 5), Some(20));
+        assert_eq!(map.set_order(&2, 10), Some(10));
+        assert_eq!(map.set_order(&3, 0), None);
+        assert_eq!(map.get(&1), Some((&5, &"a")));
+        assert_eq!(map.orders().collect::<Vec<_>>(), [&5, &10]);
+    }
+
+    #[test]
+    fn change_order_if() {
+        let mut map = Map::from([(1, 20, "a"), (2, 10, "b")]);
+        assert!(!map.change_order_if_smaller(&1, 30));
+        assert!(map.change_order_if_smaller(&1, 15));
+        assert!(!map.change_order_if_larger(&2, 10));
+        assert!(map.change_order_if_larger(&2, 25));
+        assert!(!map.change_order_if_smaller(&3, 0));
+        assert_eq!(map.get_order(&1), Some(&15));
+        assert_eq!(map.get_order(&2), Some(&25));
+        assert_eq!(map.orders().collect::<Vec<_>>(), [&15, &25]);
+    }
+
+    #[test]
+    fn adjust_order() {
+        let mut map = Map::from([(1, 20, "a"), (2, 10, "b")]);
+        assert_eq!(map.adjust_order(&2, 15), Some(10));
+        assert_eq!(map.adjust_order(&1, -5), Some(20));
+        assert_eq!(map.adjust_order(&3, 1), None);
+        assert_eq!(map.orders().collect::<Vec<_>>(), [&15, &25]);
+        assert_eq!(map.get(&2), Some((&25, &"b")));
+    }
+
+    #[test]
+    fn age() {
+        let mut map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 30, "c")]);
+        map.shift_all_orders(5);
+        assert_eq!(map.orders().collect::<Vec<_>>(), [&15, &25, &35]);
+        assert_eq!(map.get(&3), Some((&35, &"c")));
+        map.age(|order| order.min(20));
+        assert_eq!(
+            map.order_distribution().collect::<Vec<_>>(),
+            [(&15, 1), (&20, 2)]
+        );
+        assert_eq!(map.get_order(&1), Some(&20));
+    }
+
+    #[test]
+    fn map_orders() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 30, "c")]);
+        let map = map.map_orders(|key, order| (order / 20, *key));
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [
+                (&2, &(0, 2), &"b"),
+                (&1, &(1, 1), &"a"),
+                (&3, &(1, 3), &"c")
+            ]
+        );
+    }
+
+    #[test]
+    fn map_values_and_keys() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "bb"), (3, 30, "ccc")]);
+        let map = map.map_values(|key, order, value| value.len() * 100 + *order as usize + key);
+        assert_eq!(map.values().collect::<Vec<_>>(), [&212, &121, &333]);
+        let map = map.map_keys(|key| key % 2);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(&0, &10, &212), (&1, &30, &333)]
+        );
+    }
+
+    #[test]
+    fn normalize_orders() {
+        let map = Map::from([(1, 25, "a"), (2, -10, "b"), (3, 70, "c"), (4, 25, "d")]);
+        let map = map.normalize_orders();
+        assert_eq!(
+            map.order_distribution().collect::<Vec<_>>(),
+            [(&0, 1), (&1, 2), (&2, 1)]
+        );
+        assert_eq!(map.get(&3), Some((&2, &"c")));
+    }
+
+    #[test]
+    fn swap_orders() {
+        let mut map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 10, "c")]);
+        assert!(map.swap_orders(&1, &2));
+        assert_eq!(map.get(&1), Some((&10, &"a")));
+        assert_eq!(map.get(&2), Some((&20, &"b")));
+        assert!(map.swap_orders(&1, &3));
+        assert!(!map.swap_orders(&1, &4));
+        assert_eq!(
+            map.order_distribution().collect::<Vec<_>>(),
+            [(&10, 2), (&20, 1)]
+        );
+    }
+
+    #[test]
+    fn rename_key() {
+        let mut map = Map::from([(1, 20, "a"), (2, 10, "b")]);
+        assert!(map.rename_key(&1, 3));
+        assert!(!map.rename_key(&1, 4));
+        assert!(!map.rename_key(&2, 3));
+        assert!(map.rename_key(&2, 2));
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(&2, &10, &"b"), (&3, &20, &"a")]
+        );
+    }
+
+    #[test]
+    fn reassign_order() {
+        let mut map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 10, "c"), (4, 30, "d")]);
+        assert_eq!(map.reassign_order(&10, 30), 2);
+        assert_eq!(map.reassign_order(&15, 30), 0);
+        assert_eq!(
+            map.order_distribution().collect::<Vec<_>>(),
+            [(&20, 1), (&30, 3)]
+        );
+        assert_eq!(map.get(&2), Some((&30, &"b")));
+    }
+
+    #[test]
+    fn cmp_keys() {
+        let map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 10, "c")]);
+        assert_eq!(map.cmp_keys(&1, &2), Some(Ordering::Greater));
+        assert_eq!(map.cmp_keys(&2, &3), Some(Ordering::Equal));
+        assert_eq!(map.cmp_keys(&3, &1), Some(Ordering::Less));
+        assert_eq!(map.cmp_keys(&1, &4), None);
+    }
+
+    #[test]
+    fn is_smallest() {
+        let mut map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 10, "c")]);
+        assert!(map.is_smallest(&2) && map.is_smallest(&3));
+        assert!(!map.is_smallest(&1));
+        assert!(!map.is_smallest(&4));
+        map.remove_smallest();
+        assert!(map.is_smallest(&1));
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let mut map = Map::from([("a".to_string(), 20, 1), ("b".to_string(), 10, 2)]);
+        assert!(map.contains_key("a"));
+        assert_eq!(map.get("b"), Some((&10, &2)));
+        assert_eq!(map["a"], 1);
+        assert_eq!(map.set_order("a", 5), Some(20));
+        assert!(map.rename_key("a", "c".to_string()));
+        assert_eq!(map.remove("c"), Some((5, 1)));
+        assert_eq!(map.get_order("c"), None);
+    }
+
+    #[test]
+    fn borrowed_orders() {
+        let mut map = Map::from([(1, "b".to_string(), 'x'), (2, "d".to_string(), 'y')]);
+        assert!(map.contains_order("b"));
+        assert_eq!(map.group_len("d"), 1);
+        assert_eq!(map.keys_with_order("d").unwrap().next(), Some((&2, &'y')));
+        assert_eq!(
+            map.range::<str, _>((Bound::Included("a"), Bound::Excluded("c")))
+                .count(),
+            1
+        );
+        assert_eq!(map.count_below("c"), 1);
+        let other = map.split_off("c");
+        assert_eq!(other.len(), 1);
+        assert_eq!(map.drain_up_to("b").count(), 1);
+    }
+
+    #[test]
+    fn get_many_mut() {
+        let mut map = Map::from([(1, 20, 100), (2, 10, 200), (3, 30, 300)]);
+        let [a, b] = map.get_many_mut([&1, &3]).unwrap();
+        std::mem::swap(a, b);
+        assert!(map.get_many_mut([&1, &1]).is_none());
+        assert!(map.get_many_mut([&1, &4]).is_none());
+        for value in map.get_many_mut_slice(&[&2, &1]).unwrap() {
+            *value += 1;
         }
+        assert!(map.get_many_mut_slice(&[&2, &2]).is_none());
+        assert!(map.get_many_mut_slice(&[&4]).is_none());
+        assert_eq!(map.values().collect::<Vec<_>>(), [&201, &301, &100]);
     }
-}
 
-impl<K: Clone + Eq + Hash, O: Clone + Ord, V> Map<K, O, V> {
-    fn remove_ordered_key(&mut self, order: &O, key: &K) {
-        let keys = self.ordered_keys.get_mut(&order).unwrap();
-        assert!(keys.remove(key));
-        if keys.is_empty() {
-            assert!(self.ordered_keys.remove(&order).is_some());
+    #[test]
+    fn peek_smallest_mut() {
+        let mut map = Map::from([(1, 20, 100), (2, 10, 200)]);
+        let mut smallest = map.peek_smallest_mut().unwrap();
+        assert_eq!((smallest.key(), smallest.order()), (&2, &10));
+        *smallest += 1;
+        smallest.set_order(30);
+        assert_eq!(smallest.order(), &30);
+        drop(smallest);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(&1, &20, &100), (&2, &30, &201)]
+        );
+        let smallest = map.peek_smallest_mut().unwrap();
+        assert_eq!(PeekSmallestMut::pop(smallest), (1, 20, 100));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn order_mut() {
+        let mut map = Map::from([(1, 20, "a"), (2, 10, "b")]);
+        let mut order = map.order_mut(&2).unwrap();
+        assert_eq!((order.key(), *order), (&2, 10));
+        *order += 15;
+        drop(order);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(&1, &20, &"a"), (&2, &25, &"b")]
+        );
+        *map.order_mut(&1).unwrap() = 20;
+        assert_eq!(map.group_count(), 2);
+        assert!(map.order_mut(&3).is_none());
+    }
+
+    #[test]
+    fn cursor() {
+        let mut map = Map::from([(1, 10, "a"), (2, 20, "b"), (3, 20, "c"), (4, 30, "d")]);
+        let mut cursor = map.lower_bound(Bound::Excluded(&10));
+        assert_eq!(cursor.order(), Some(&20));
+        assert_eq!(cursor.group().unwrap().len(), 2);
+        assert_eq!(
+            (cursor.peek_prev(), cursor.peek_next()),
+            (Some(&10), Some(&30))
+        );
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.order(), None);
+        cursor.move_next();
+        assert_eq!(cursor.order(), Some(&10));
+        assert_eq!(map.upper_bound(Bound::Included(&25)).order(), Some(&20));
+
+        let mut cursor = map.upper_bound_mut(Bound::<&i32>::Unbounded);
+        assert_eq!(cursor.order(), Some(&30));
+        cursor.add(5, "e");
+        cursor.move_prev();
+        assert_eq!(cursor.remove_entry(&1), None);
+        assert_eq!(cursor.remove_entry(&2), Some((2, "b")));
+        let (order, group) = cursor.remove_current().unwrap();
+        assert_eq!((order, group), (20, vec![(3, "c")]));
+        assert_eq!(cursor.order(), Some(&30));
+        assert_eq!(map.len(), 3);
+        assert_eq!(
+            map.order_distribution().collect::<Vec<_>>(),
+            [(&10, 1), (&30, 2)]
+        );
+    }
+
+    #[test]
+    fn first_and_last_entry() {
+        let mut map = Map::from([(1, 10, "a"), (2, 20, "b"), (3, 30, "c"), (4, 30, "d")]);
+        let mut last = map.last_entry().unwrap();
+        assert_eq!((last.order(), last.len()), (&30, 2));
+        last.set_order(40);
+        let first = map.first_entry().unwrap();
+        assert_eq!(first.iter().collect::<Vec<_>>(), [(&1, &"a")]);
+        assert_eq!(first.drain().collect::<Vec<_>>(), [(1, "a")]);
+        assert_eq!(map.orders().collect::<Vec<_>>(), [&20, &40]);
+        map.clear();
+        assert!(map.first_entry().is_none() && map.last_entry().is_none());
+    }
+
+    #[test]
+    fn groups() {
+        let map = Map::from([(1, 10, "a"), (2, 20, "b"), (3, 20, "c")]);
+        let groups: Vec<_> = map
+            .groups()
+            .map(|(order, group)| (order, sort(group.collect(), |v| v)))
+            .collect();
+        assert_eq!(
+            groups,
+            [(&10, vec![(&1, &"a")]), (&20, vec![(&2, &"b"), (&3, &"c")])]
+        );
+        assert_eq!(map.groups().len(), 2);
+        let groups: Vec<_> = map
+            .into_groups()
+            .rev()
+            .map(|(order, group)| (order, sort(group, |v| v)))
+            .collect();
+        assert_eq!(
+            groups,
+            [(20, vec![(2, "b"), (3, "c")]), (10, vec![(1, "a")])]
+        );
+    }
+
+    #[test]
+    fn views_of_internals() {
+        let map = Map::from([(1, 10, "a"), (2, 20, "b"), (3, 20, "c")]);
+        let groups = map.as_groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[&20], HashSet::from_iter([2, 3]));
+        assert_eq!(map.as_unordered()[&3], (20, "c"));
+    }
+
+    #[test]
+    fn ordmap_macro() {
+        let map = ordmap! { 1 => (20, "a"), 2 => (10, "b") };
+        assert_eq!(map, Map::from([(1, 20, "a"), (2, 10, "b")]));
+        let map: Map<i32, i32, &str> = ordmap! {};
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn builder() {
+        let mut map: Map<i32, i32, &str> = MapBuilder::new().capacity(10).build();
+        assert!(map.capacity() >= 10);
+        map.add(1, 20, "a");
+        let map: Map<i32, i32, &str, core::hash::BuildHasherDefault<std::hash::DefaultHasher>> =
+            MapBuilder::new().hasher(Default::default()).build();
+        assert!(map.is_empty());
+
+        let entries = [(1, 20, "a"), (2, 10, "b"), (1, 30, "c")];
+        let map = MapBuilder::new()
+            .tie_break(ByKey)
+            .duplicate_policy(DuplicatePolicy::KeepExisting)
+            .build_from(entries)
+            .unwrap();
+        assert_eq!(map.get(&1), Some((&20, &"a")));
+        let error = MapBuilder::new()
+            .duplicate_policy(DuplicatePolicy::Error)
+            .build_from(entries)
+            .unwrap_err();
+        assert_eq!(error.key, 1);
+
+        #[cfg(feature = "heapless")]
+        {
+            let mut bounded = MapBuilder::new()
+                .capacity(1)
+                .build_bounded::<i32, i32, &str, 1>();
+            assert_eq!(bounded.add(1, 20, "a"), Ok(None));
+            assert_eq!(bounded.add(2, 10, "b"), Err((2, 10, "b")));
+            assert!(std::panic::catch_unwind(|| {
+                MapBuilder::new()
+                    .capacity(2)
+                    .build_bounded::<i32, i32, &str, 1>()
+            })
+            .is_err());
         }
     }
 
-    /// Removes an entry by key.
-    pub fn remove(&mut self, key: &K) -> Option<(O, V)> {
-        let (order, value) = self.values.remove(key)?;
-        self.remove_ordered_key(&order, key);
-        Some((order, value))
+    #[test]
+    fn try_add() {
+        let mut map = Map::new();
+        assert_eq!(map.try_add(1, 20, "a"), Ok(()));
+        let error = map.try_add(1, 10, "b").unwrap_err();
+        assert_eq!(
+            error,
+            OccupiedError {
+                key: 1,
+                order: 10,
+                value: "b"
+            }
+        );
+        assert_eq!(error.to_string(), "the key already has an entry");
+        assert!(!map.add_if_absent(1, 10, "b"));
+        assert!(map.add_if_absent(2, 10, "b"));
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(&2, &10, &"b"), (&1, &20, &"a")]
+        );
     }
 
-    /// Removes entries with the smallest order value. Items in the result are not ordered.
-    pub fn remove_smallest(&mut self) -> Option<(O, Vec<(K, V)>)> {
-        let (order, keys) = self.ordered_keys.pop_first()?;
-        let mut smallest = Vec::new();
-        for key in keys {
-            let (_order, value) = self.values.remove(&key).unwrap();
-            smallest.push((key, value));
+    #[test]
+    fn get_or_insert_with() {
+        let mut map = Map::from([(1, 20, 100)]);
+        let (order, value) = map.get_or_insert_with(1, || unreachable!());
+        *value += 1;
+        assert_eq!(*order, 20);
+        assert_eq!(map.get_or_insert_with(2, || (10, 200)), (&10, &mut 200));
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(&2, &10, &200), (&1, &20, &101)]
+        );
+    }
+
+    #[test]
+    fn duplicate_policy() {
+        let entries = [(1, 20, 100), (2, 10, 200), (1, 30, 300)];
+        let map: Map<_, _, _> =
+            Map::from_iter_with_policy(entries, DuplicatePolicy::KeepExisting).unwrap();
+        assert_eq!(map.get(&1), Some((&20, &100)));
+        let map: Map<_, _, _> =
+            Map::from_iter_with_policy(entries, DuplicatePolicy::Overwrite).unwrap();
+        assert_eq!(map.get(&1), Some((&30, &300)));
+        let error =
+            Map::<_, _, _>::from_iter_with_policy(entries, DuplicatePolicy::Error).unwrap_err();
+        assert_eq!(
+            error,
+            OccupiedError {
+                key: 1,
+                order: 30,
+                value: 300
+            }
+        );
+
+        let mut map = Map::new();
+        let mut sum = |_: &i32, (o1, v1), (o2, v2)| (i32::min(o1, o2), v1 + v2);
+        map.extend_with_policy(entries, DuplicatePolicy::Merge(&mut sum))
+            .unwrap();
+        map.add_with_policy(2, 5, 1, DuplicatePolicy::Merge(&mut sum))
+            .unwrap();
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(&2, &5, &201), (&1, &20, &400)]
+        );
+    }
+
+    #[test]
+    fn update() {
+        let mut map = Map::from([(1, 20, 100), (2, 10, 200)]);
+        assert!(map.update(&1, |order, value| {
+            *order = 5;
+            *value += 1;
+        }));
+        assert!(map.update(&2, |_order, value| *value += 1));
+        assert!(!map.update(&3, |_order, _value| unreachable!()));
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(&1, &5, &101), (&2, &10, &201)]
+        );
+    }
+
+    #[test]
+    fn add_batch() {
+        let mut map = Map::from([(1, 20, "a")]);
+        let replaced = map.add_batch([(2, 10, "b"), (1, 30, "c"), (2, 5, "d")]);
+        assert_eq!(replaced, [(1, 20, "a"), (2, 10, "b")]);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(&2, &5, &"d"), (&1, &30, &"c")]
+        );
+
+        assert_eq!(map.add_batch([(1, 30, "e")]), [(1, 30, "c")]);
+        assert_eq!(map.ordered_keys[&30], HashSet::from_iter([1]));
+        assert_eq!(map.group_count(), 2);
+    }
+
+    #[test]
+    fn remove_many() {
+        let mut map = Map::from([(1, 20, "a"), (2, 10, "b"), (3, 10, "c")]);
+        assert_eq!(map.remove_many(&[3, 4, 1]), [(3, 10, "c"), (1, 20, "a")]);
+        assert_eq!(map.iter().collect::<Vec<_>>(), [(&2, &10, &"b")]);
+    }
+
+    #[test]
+    fn retain_and_remove_keys() {
+        let entries = [(1, 20, "a"), (2, 10, "b"), (3, 10, "c")];
+        let mut map = Map::from(entries);
+        let capacity = map.capacity();
+        map.retain_keys(&HashSet::from_iter([1, 3]));
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(&3, &10, &"c"), (&1, &20, &"a")]
+        );
+        map.retain_keys(&HashSet::from_iter([1, 2, 4, 5]));
+        assert_eq!(map.keys().collect::<Vec<_>>(), [&1]);
+        assert_eq!(map.group_count(), 1);
+        assert_eq!(map.capacity(), capacity);
+
+        let mut map = Map::from(entries);
+        map.remove_keys(&HashSet::from_iter([2]));
+        assert_eq!(map.keys().collect::<Vec<_>>(), [&3, &1]);
+        map.remove_keys(&std::collections::HashSet::from([1, 4, 5]));
+        assert_eq!(map.keys().collect::<Vec<_>>(), [&3]);
+        assert_eq!(map.group_count(), 1);
+
+        let mut map = FifoMap::<_, _, _>::default();
+        for i in 0..100 {
+            map.add(i, i % 3, ());
         }
-        Some((order, smallest))
+        map.retain_keys(&alloc::collections::BTreeSet::from([97, 3, 9, 4, 200]));
+        assert_eq!(map.keys().collect::<Vec<_>>(), [&3, &9, &4, &97]);
+        assert_eq!(map.group_count(), 2);
+        map.retain_keys(&std::collections::HashSet::from([9]));
+        assert_eq!(map.keys().collect::<Vec<_>>(), [&9]);
     }
 
-    /// Returns references to entries with the smallest order value. The references are unordered.
-    pub fn peek_smallest(&self) -> Option<(&O, Vec<(&K, &V)>)> {
-        let (order, keys) = self.ordered_keys.first_key_value()?;
-        let mut smallest = Vec::new();
-        for key in keys {
-            let (_order, value) = self.values.get(&key).unwrap();
-            smallest.push((key, value));
+    #[test]
+    fn has_order() {
+        struct Job {
+            deadline: u32,
         }
-        Some((order, smallest))
+        impl HasOrder<u32> for Job {
+            fn order(&self) -> u32 {
+                self.deadline
+            }
+        }
+        let mut map = Map::new();
+        map.add_auto("a", Job { deadline: 20 });
+        map.add_auto("b", Job { deadline: 10 });
+        assert_eq!(map.get_order("a"), Some(&20));
+        assert!(map.update_value_auto("a", |job| job.deadline = 5));
+        assert!(!map.update_value_auto("c", |_job| unreachable!()));
+        assert_eq!(map.orders().collect::<Vec<_>>(), [&5, &10]);
     }
 
-    /// Returns the old entry with the same key if there was one.
-    pub fn add(&mut self, key: K, order: O, value: V) -> Option<(O, V)> {
-        let old_entry = if let Some((old_order, old_value)) = self.values.remove(&key) {
-            self.remove_ordered_key(&old_order, &key);
-            Some((old_order, old_value))
-        } else {
-            None
-        };
-        assert!(self
-            .ordered_keys
-            .entry(order.clone())
-            .or_insert_with(|| HashSet::new())
-            .insert(key.clone()));
-        assert!(self.values.insert(key, (order, value)).is_none());
-        old_entry
+    #[test]
+    fn float_orders() {
+        let mut map = Map::new();
+        map.add("a", F64Ord(2.5), ());
+        map.add("b", F64Ord::from(f64::NAN), ());
+        map.add("c", (-1.0).into(), ());
+        map.add("d", F64Ord(-0.0), ());
+        let orders: Vec<f64> = map.orders().map(|&order| order.into()).collect();
+        assert_eq!(orders[..3], [-1.0, -0.0, 2.5]);
+        assert!(orders[3].is_nan());
+        assert_eq!(F32Ord(0.5), F32Ord::from(0.5));
+        assert!(F32Ord(-0.0) < F32Ord(0.0));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn comparator() {
+        #[derive(Default)]
+        struct CaseInsensitive;
+        impl Compare<&str> for CaseInsensitive {
+            fn compare(&self, a: &&str, b: &&str) -> Ordering {
+                a.to_lowercase().cmp(&b.to_lowercase())
+            }
+        }
+        let mut map: ComparatorMap<i32, &str, (), CaseInsensitive> =
+            Map::with_comparator(CaseInsensitive);
+        map.add(1, "b", ());
+        map.add(2, "A", ());
+        map.add(3, "B", ());
+        map.add(4, "c", ());
+        assert_eq!(map.group_count(), 3);
+        assert_eq!(map.min_order(), Some(&"A"));
+        assert_eq!(map.count_in_range("a"..="B"), 3);
+        assert_eq!(map.remove_largest(), Some(("c", vec![(4, ())])));
+        let (order, group) = map.drain_smallest().unwrap();
+        assert_eq!((order, group.count()), ("A", 1));
 
-    fn sort<O, T: Ord, F: FnOnce(&mut O) -> &mut Vec<T>>(mut value: O, grabber: F) -> O {
-        grabber(&mut value).sort();
-        value
+        #[derive(Default)]
+        struct ByLength;
+        impl Compare<&str> for ByLength {
+            fn compare(&self, a: &&str, b: &&str) -> Ordering {
+                a.len().cmp(&b.len())
+            }
+        }
+        let mut map = Map::with_comparator(ByLength);
+        map.add(1, "bb", 10);
+        map.add(2, "a", 20);
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            [(&2, &"a", &20), (&1, &"bb", &10)]
+        );
+        assert_eq!(map.set_order(&2, "cc"), Some("a"));
+        assert_eq!(map.group_len(&"zz"), 2);
+        assert!(map.contains_order(&"xx"));
+        assert_eq!(CompareBy::<_, ByLength>::from_ref(&"x").order, "x");
+        assert_eq!(CompareBy::<_, CaseInsensitive>::from("x").into_inner(), "x");
     }
 
-    fn maybe_sort<O, T: Ord, F: FnOnce(&mut O) -> &mut Vec<T>>(
-        mut value: Option<O>,
-        grabber: F,
-    ) -> Option<O> {
-        if let Some(value) = &mut value {
-            grabber(value).sort();
+    #[test]
+    fn max_map() {
+        let mut map: MaxMap<_, _, _> = [(1, 20, "a"), (2, 10, "b"), (3, 30, "c")]
+            .into_iter()
+            .collect();
+        assert_eq!(map.add(4, 30, "d"), None);
+        assert_eq!(map.max_order(), Some(&30));
+        assert_eq!(map.get(&2), Some((&10, &"b")));
+        assert_eq!(map.set_order(&2, 40), Some(10));
+        assert_eq!(map.pop_one_largest(), Some((2, 40, "b")));
+        let (order, group) = map.remove_largest().unwrap();
+        assert_eq!((order, sort(group, |v| v)), (30, vec![(3, "c"), (4, "d")]));
+        assert_eq!(map.iter().collect::<Vec<_>>(), [(&1, &20, &"a")]);
+        assert_eq!(format!("{map:?}"), r#"[(1, 20, "a")]"#);
+
+        let mut map = MaxMap::from([(1, 10, "a"), (2, 20, "b"), (3, 30, "c"), (4, 40, "d")]);
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), [4, 3, 2, 1]);
+        assert_eq!(
+            map.range(20..40).map(|(key, ..)| *key).collect::<Vec<_>>(),
+            [3, 2]
+        );
+        assert_eq!(map.count_above(&20), 2);
+        assert_eq!(map.rank(&2), Some(2));
+        assert_eq!(map.median_order(), Some(&30));
+        assert_eq!(map.percentile_order(0.0), Some(&40));
+        assert!(map.change_order_if_larger(&1, 50));
+        assert_eq!(map.remove_smallest(), Some((20, vec![(2, "b")])));
+        assert_eq!(map.remove_largest_n(2), [(1, 50, "a"), (4, 40, "d")]);
+        assert_eq!(map.drain_down_to(&30).collect::<Vec<_>>(), [(3, 30, "c")]);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn fifo_map() {
+        let mut map: FifoMap<_, _, _> = [(1, 10, "a"), (2, 5, "b"), (3, 10, "c"), (4, 10, "d")]
+            .into_iter()
+            .collect();
+        assert_eq!(map.set_order(&1, 20), Some(10));
+        assert_eq!(map.set_order(&1, 10), Some(20));
+        assert_eq!(map.add(3, 10, "c"), Some((10, "c")));
+        assert_eq!(map.pop_one_smallest(), Some((2, 5, "b")));
+        assert_eq!(map.pop_one_smallest(), Some((4, 10, "d")));
+        map.add(5, 10, "e");
+        assert_eq!(
+            map.peek_smallest(),
+            Some((&10, vec![(&1, &"a"), (&3, &"c"), (&5, &"e")]))
+        );
+        assert_eq!(
+            map.remove_smallest(),
+            Some((10, vec![(1, "a"), (3, "c"), (5, "e")]))
+        );
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn tie_break_policies() {
+        let entries = [(3, 10, "a"), (1, 10, "b"), (2, 10, "c")];
+        let mut fifo = MapBuilder::new().tie_break(Fifo).build();
+        fifo.extend(entries);
+        assert_eq!(fifo.pop_one_smallest(), Some((3, 10, "a")));
+        let mut lifo: LifoMap<_, _, _> = entries.into_iter().collect();
+        assert_eq!(lifo.keys().collect::<Vec<_>>(), [&2, &1, &3]);
+        assert_eq!(lifo.pop_one_smallest(), Some((2, 10, "c")));
+        let mut by_key = MapBuilder::new().tie_break(ByKey).build();
+        by_key.extend(entries);
+        assert_eq!(by_key.set_order(&1, 10), Some(10));
+        let (order, group) = by_key.remove_smallest().unwrap();
+        assert_eq!((order, group), (10, vec![(1, "b"), (2, "c"), (3, "a")]));
+        let mut unspecified = MapBuilder::new().tie_break(Unspecified).build();
+        unspecified.extend(entries);
+        let (order, group) = unspecified.remove_smallest().unwrap();
+        assert_eq!(
+            (order, sort(group, |v| v)),
+            (10, vec![(1, "b"), (2, "c"), (3, "a")])
+        );
+    }
+
+    #[test]
+    fn sorted_map() {
+        let mut map: SortedMap<_, _, _> = [(3, 10, "a"), (1, 10, "b"), (2, 5, "c"), (4, 10, "d")]
+            .into_iter()
+            .collect();
+        assert_eq!(map.set_order(&2, 10), Some(5));
+        assert_eq!(map.remove(&4), Some((10, "d")));
+        assert_eq!(format!("{map:?}"), r#"{10: {1: "b", 2: "c", 3: "a"}}"#);
+        assert_eq!(
+            map.peek_smallest(),
+            Some((&10, vec![(&1, &"b"), (&2, &"c"), (&3, &"a")]))
+        );
+        assert_eq!(map.pop_one_smallest(), Some((1, 10, "b")));
+        assert_eq!(map.remove_smallest(), Some((10, vec![(2, "c"), (3, "a")])));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn sorted_map_is_deterministic() {
+        let mut map = SortedMap::default();
+        for (key, order) in [(5, 2), (3, 1), (4, 2), (1, 1), (2, 2)] {
+            map.entry(key).or_insert(order, key * 10);
         }
-        value
+        let keys = |map: &SortedMap<i32, i32, i32>| map.keys().copied().collect::<Vec<_>>();
+        assert_eq!(keys(&map), [1, 3, 2, 4, 5]);
+        assert_eq!(
+            map.keys().rev().copied().collect::<Vec<_>>(),
+            [5, 4, 2, 3, 1]
+        );
+        let groups: Vec<_> = map
+            .groups()
+            .map(|(order, group)| (*order, group.map(|(key, _value)| *key).collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(groups, [(1, vec![1, 3]), (2, vec![2, 4, 5])]);
+        assert_eq!(
+            map.remove_smallest_n(3),
+            [(1, 1, 10), (3, 1, 30), (2, 2, 20)]
+        );
+        assert_eq!(map.clone().into_iter().next_back(), Some((5, 2, 50)));
+        let OrderEntry::Occupied(group) = map.order_entry(2) else {
+            panic!("the group of order 2 is missing");
+        };
+        assert_eq!(group.drain().collect::<Vec<_>>(), [(4, 40), (5, 50)]);
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn group_containers() {
+        let entries = [(3, 10, "a"), (1, 10, "b"), (2, 5, "c"), (4, 20, "d")];
+        let mut map: Map<_, _, _, DefaultHashBuilder, BTreeSet<_>> = entries.into_iter().collect();
+        assert_eq!(map.set_order(&2, 10), Some(5));
+        assert_eq!(map.keys().rev().collect::<Vec<_>>(), [&4, &3, &2, &1]);
+        assert_eq!(
+            map.range(..20).map(|(key, ..)| *key).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        let mut keys = map.clone().into_keys();
+        assert_eq!(
+            (keys.next(), keys.next_back(), keys.next()),
+            (Some(1), Some(4), Some(2))
+        );
+        assert_eq!(map.iter_mut().rev().nth(1).map(|(key, ..)| *key), Some(3));
+        let (order, group) = map.remove_smallest().unwrap();
+        assert_eq!((order, group), (10, vec![(1, "b"), (2, "c"), (3, "a")]));
+    }
+
+    #[test]
+    #[cfg(feature = "indexmap")]
+    fn index_set_groups() {
+        type IndexSet<K> = indexmap::IndexSet<K, DefaultHashBuilder>;
+        let entries = [(3, 10, "a"), (1, 10, "b"), (2, 10, "c")];
+        let mut map: Map<_, _, _, DefaultHashBuilder, IndexSet<_>> = entries.into_iter().collect();
+        assert_eq!(map.remove(&1), Some((10, "b")));
+        assert_eq!(map.pop_one_smallest(), Some((3, 10, "a")));
+        assert_eq!(map.peek_smallest(), Some((&10, vec![(&2, &"c")])));
+    }
+
+    #[test]
+    #[cfg(feature = "smallvec")]
+    fn small_vec_groups() {
+        type SmallVec<K> = smallvec::SmallVec<[K; 2]>;
+        let entries = [(3, 10, "a"), (1, 10, "b"), (2, 10, "c")];
+        let mut map: Map<_, _, _, DefaultHashBuilder, SmallVec<_>> = entries.into_iter().collect();
+        assert_eq!(map.set_order(&3, 5), Some(10));
+        assert_eq!(map.set_order(&3, 10), Some(5));
+        let (order, group) = map.remove_smallest().unwrap();
+        assert_eq!((order, group), (10, vec![(1, "b"), (2, "c"), (3, "a")]));
     }
 
     #[test]
@@ -95,28 +3594,40 @@ mod tests {
         let mut map = Map::new();
 
         assert_eq!(map.add(5, 2, "a"), None);
-        assert_eq!(map.ordered_keys, BTreeMap::from([(2, HashSet::from([5]))]));
-        assert_eq!(map.values, HashMap::from([(5, (2, "a"))]));
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(2, HashSet::from_iter([5]))])
+        );
+        assert_eq!(map.values, HashMap::from_iter([(5, (2, "a"))]));
 
         assert_eq!(map.add(5, 5, "b"), Some((2, "a")));
-        assert_eq!(map.ordered_keys, BTreeMap::from([(5, HashSet::from([5]))]));
-        assert_eq!(map.values, HashMap::from([(5, (5, "b"))]));
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(5, HashSet::from_iter([5]))])
+        );
+        assert_eq!(map.values, HashMap::from_iter([(5, (5, "b"))]));
 
         assert_eq!(map.add(6, 2, "c"), None);
         assert_eq!(
             map.ordered_keys,
-            BTreeMap::from([(5, HashSet::from([5])), (2, HashSet::from([6]))])
+            BTreeMap::from([(5, HashSet::from_iter([5])), (2, HashSet::from_iter([6]))])
+        );
+        assert_eq!(
+            map.values,
+            HashMap::from_iter([(5, (5, "b")), (6, (2, "c"))])
         );
-        assert_eq!(map.values, HashMap::from([(5, (5, "b")), (6, (2, "c"))]));
 
         assert_eq!(map.add(7, 2, "d"), None);
         assert_eq!(
             map.ordered_keys,
-            BTreeMap::from([(5, HashSet::from([5])), (2, HashSet::from([6, 7]))])
+            BTreeMap::from([
+                (5, HashSet::from_iter([5])),
+                (2, HashSet::from_iter([6, 7]))
+            ])
         );
         assert_eq!(
             map.values,
-            HashMap::from([(5, (5, "b")), (6, (2, "c")), (7, (2, "d"))])
+            HashMap::from_iter([(5, (5, "b")), (6, (2, "c")), (7, (2, "d"))])
         );
 
         map.add(8, 1, "e");
@@ -124,21 +3635,27 @@ mod tests {
         assert_eq!(map.remove(&8), Some((1, "e")));
         assert_eq!(
             map.ordered_keys,
-            BTreeMap::from([(5, HashSet::from([5])), (2, HashSet::from([6, 7]))])
+            BTreeMap::from([
+                (5, HashSet::from_iter([5])),
+                (2, HashSet::from_iter([6, 7]))
+            ])
         );
         assert_eq!(
             map.values,
-            HashMap::from([(5, (5, "b")), (6, (2, "c")), (7, (2, "d"))])
+            HashMap::from_iter([(5, (5, "b")), (6, (2, "c")), (7, (2, "d"))])
         );
 
         assert_eq!(map.remove(&8), None);
         assert_eq!(
             map.ordered_keys,
-            BTreeMap::from([(5, HashSet::from([5])), (2, HashSet::from([6, 7]))])
+            BTreeMap::from([
+                (5, HashSet::from_iter([5])),
+                (2, HashSet::from_iter([6, 7]))
+            ])
         );
         assert_eq!(
             map.values,
-            HashMap::from([(5, (5, "b")), (6, (2, "c")), (7, (2, "d"))])
+            HashMap::from_iter([(5, (5, "b")), (6, (2, "c")), (7, (2, "d"))])
         );
 
         assert_eq!(
@@ -150,8 +3667,11 @@ mod tests {
             maybe_sort(map.remove_smallest(), |r| &mut r.1),
             Some((2, sort(vec![(6, "c"), (7, "d")], |v| v)))
         );
-        assert_eq!(map.ordered_keys, BTreeMap::from([(5, HashSet::from([5]))]));
-        assert_eq!(map.values, HashMap::from([(5, (5, "b"))]));
+        assert_eq!(
+            map.ordered_keys,
+            BTreeMap::from([(5, HashSet::from_iter([5]))])
+        );
+        assert_eq!(map.values, HashMap::from_iter([(5, (5, "b"))]));
 
         assert_eq!(
             maybe_sort(map.remove_smallest(), |r| &mut r.1),
@@ -160,6 +3680,7 @@ mod tests {
 
         assert!(map.values.is_empty());
         assert!(map.ordered_keys.is_empty());
+        assert!(map.is_empty());
 
         assert_eq!(map.peek_smallest(), None);
         assert_eq!(map.remove_smallest(), None);
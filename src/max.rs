@@ -0,0 +1,187 @@
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::{Ordering, Reverse};
+use core::hash::{BuildHasher, Hash};
+use core::ops::Bound;
+
+use hashbrown::HashSet;
+
+use crate::wrapped::{unwrap_entries, DrainRange, OrderWrapper, WrappedMap};
+use crate::{DefaultHashBuilder, GroupDrain, GroupIter, KeySet};
+
+/// A map like [`Map`](crate::Map) which serves the entries with the largest orders first.
+///
+/// The orders are stored wrapped in [`Reverse`], so the map order is descending by order. The
+/// methods of [`Map`](crate::Map) named after the smallest orders are named after the largest
+/// ones here, and the other way around.
+pub type MaxMap<K, O, V, S = DefaultHashBuilder, G = HashSet<K, S>> =
+    WrappedMap<K, O, V, Reverse<O>, S, G>;
+
+impl<K, O, V, S, G> MaxMap<K, O, V, S, G>
+where
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    S: BuildHasher + Clone,
+    G: KeySet<K, S>,
+{
+    /// Returns the largest order, without looking at the entries that have it.
+    pub fn max_order(&self) -> Option<&O> {
+        Some(&self.map.min_order()?.0)
+    }
+
+    /// Returns the smallest order, without looking at the entries that have it.
+    pub fn min_order(&self) -> Option<&O> {
+        Some(&self.map.max_order()?.0)
+    }
+
+    /// Returns `true` if the entry with the key has the largest order, so it would be removed by
+    /// the next [`MaxMap::remove_largest`].
+    pub fn is_largest<Q: Eq + Hash + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map.is_smallest(key)
+    }
+
+    /// Compares the orders of two entries. Returns `None` if either key has no entry.
+    pub fn cmp_keys<Q: Eq + Hash + ?Sized>(&self, a: &Q, b: &Q) -> Option<Ordering>
+    where
+        K: Borrow<Q>,
+    {
+        Some(self.map.cmp_keys(a, b)?.reverse())
+    }
+
+    /// Returns the number of entries with orders greater than `order`.
+    pub fn count_above(&self, order: &O) -> usize {
+        self.map.count_below(Reverse::from_ref(order))
+    }
+
+    /// Removes the entries with orders greater than or equal to `order`, returning them as an
+    /// iterator descending by order.
+    pub fn drain_down_to(&mut self, order: &O) -> DrainRange<'_, K, O, V, Reverse<O>, S> {
+        self.drain_range((Bound::Included(order), Bound::Unbounded))
+    }
+
+    /// Removes the entries with orders greater than `order`, returning them as an iterator
+    /// descending by order.
+    pub fn drain_above(&mut self, order: &O) -> DrainRange<'_, K, O, V, Reverse<O>, S> {
+        self.drain_range((Bound::Excluded(order), Bound::Unbounded))
+    }
+
+    /// Consumes the map, returning a map with the orders replaced by `f` of the key and the order.
+    /// The entries are visited in arbitrary order.
+    pub fn map_orders<O2: Clone + Ord, F: FnMut(&K, O) -> O2>(
+        self,
+        mut f: F,
+    ) -> MaxMap<K, O2, V, S, G> {
+        MaxMap::from_map(
+            self.map
+                .map_orders(|key, Reverse(order)| Reverse(f(key, order))),
+        )
+    }
+
+    /// Returns the `n`-th largest distinct order, counting from zero.
+    pub fn nth_largest_order(&self, n: usize) -> Option<&O> {
+        Some(&self.map.nth_smallest_order(n)?.0)
+    }
+
+    /// Returns the `n`-th entry descending by order, counting from zero.
+    pub fn nth_largest_entry(&self, n: usize) -> Option<(&K, &O, &V)> {
+        let (key, Reverse(order), value) = self.map.nth_smallest_entry(n)?;
+        Some((key, order, value))
+    }
+
+    /// Removes entries with the largest order value. Items in the result are not ordered.
+    pub fn remove_largest(&mut self) -> Option<(O, Vec<(K, V)>)> {
+        let (Reverse(order), group) = self.map.remove_smallest()?;
+        Some((order, group))
+    }
+
+    /// Removes a single entry with the largest order value, the one of its group which is served
+    /// first.
+    pub fn pop_one_largest(&mut self) -> Option<(K, O, V)> {
+        let (key, Reverse(order), value) = self.map.pop_one_smallest()?;
+        Some((key, order, value))
+    }
+
+    /// Removes up to `n` entries with the largest orders, returning them descending by order.
+    pub fn remove_largest_n(&mut self, n: usize) -> Vec<(K, O, V)> {
+        unwrap_entries(self.map.remove_smallest_n(n))
+    }
+
+    /// Removes entries descending by order for as long as `pred` returns `true`, stopping at the
+    /// first entry for which it returns `false`, which is kept. Returns the removed entries.
+    pub fn remove_largest_while<F: FnMut(&K, &O, &V) -> bool>(
+        &mut self,
+        mut pred: F,
+    ) -> Vec<(K, O, V)> {
+        unwrap_entries(
+            self.map
+                .remove_smallest_while(|key, Reverse(order), value| pred(key, order, value)),
+        )
+    }
+
+    /// Removes entries with the largest order value, appending them to `group` in no particular
+    /// order. Returns the order, or `None` if the map is empty.
+    pub fn remove_largest_into(&mut self, group: &mut Vec<(K, V)>) -> Option<O> {
+        Some(self.map.remove_smallest_into(group)?.0)
+    }
+
+    /// Appends references to entries with the largest order value to `group` in no particular
+    /// order. Returns the order, or `None` if the map is empty.
+    pub fn peek_largest_into<'a>(&'a self, group: &mut Vec<(&'a K, &'a V)>) -> Option<&'a O> {
+        Some(&self.map.peek_smallest_into(group)?.0)
+    }
+
+    /// Returns references to entries with the largest order value. The references are unordered.
+    pub fn peek_largest(&self) -> Option<(&O, Vec<(&K, &V)>)> {
+        let (Reverse(order), group) = self.map.peek_smallest()?;
+        Some((order, group))
+    }
+
+    /// Returns the largest order value and an iterator over the keys and values of its entries,
+    /// without allocating. The entries are not ordered.
+    #[allow(clippy::type_complexity)]
+    pub fn peek_largest_iter(&self) -> Option<(&O, GroupIter<'_, K, Reverse<O>, V, S, G>)> {
+        let (Reverse(order), group) = self.map.peek_smallest_iter()?;
+        Some((order, group))
+    }
+
+    /// Removes the group with the largest order value, returning the order and an iterator over
+    /// the entries of the group. The entries are not ordered.
+    #[allow(clippy::type_complexity)]
+    pub fn drain_largest(&mut self) -> Option<(O, GroupDrain<'_, K, Reverse<O>, V, S, G>)> {
+        let (Reverse(order), group) = self.map.drain_smallest()?;
+        Some((order, group))
+    }
+
+    /// Removes entries with the smallest order value. Items in the result are not ordered.
+    pub fn remove_smallest(&mut self) -> Option<(O, Vec<(K, V)>)> {
+        let (Reverse(order), group) = self.map.remove_largest()?;
+        Some((order, group))
+    }
+
+    /// Returns references to entries with the smallest order value. The references are unordered.
+    pub fn peek_smallest(&self) -> Option<(&O, Vec<(&K, &V)>)> {
+        let (Reverse(order), group) = self.map.peek_largest()?;
+        Some((order, group))
+    }
+
+    /// Moves an entry to a new order if it is larger than the current one, which is the
+    /// increase-key operation of a max-priority queue. Returns `true` if the order was changed.
+    pub fn change_order_if_larger<Q: Eq + Hash + ?Sized>(&mut self, key: &Q, order: O) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map.change_order_if_smaller(key, Reverse(order))
+    }
+
+    /// Moves an entry to a new order if it is smaller than the current one. Returns `true` if the
+    /// order was changed.
+    pub fn change_order_if_smaller<Q: Eq + Hash + ?Sized>(&mut self, key: &Q, order: O) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map.change_order_if_larger(key, Reverse(order))
+    }
+}
@@ -0,0 +1,154 @@
+use core::cmp::Ordering;
+use core::fmt::{self, Debug};
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+
+/// A value which determines its own order in a [`Map`](crate::Map).
+///
+/// Used by [`Map::add_auto`](crate::Map::add_auto) and
+/// [`Map::update_value_auto`](crate::Map::update_value_auto) to keep the orders in sync with the
+/// values.
+pub trait HasOrder<O> {
+    /// Returns the order of the value.
+    fn order(&self) -> O;
+}
+
+macro_rules! float_ord {
+    ($name:ident, $float:ty) => {
+        #[doc = concat!(
+            "An `", stringify!($float), "` ordered by [`", stringify!($float), "::total_cmp`], so it",
+            " can be used as an order."
+        )]
+        ///
+        /// Negative zero is smaller than positive zero, and NaNs are ordered by their sign and
+        /// payload: positive NaNs are larger than infinity and negative NaNs are smaller than
+        /// negative infinity.
+        #[derive(Clone, Copy, Debug, Default)]
+        pub struct $name(pub $float);
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                self.cmp(other).is_eq()
+            }
+        }
+
+        impl Eq for $name {}
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.0.total_cmp(&other.0)
+            }
+        }
+
+        impl Hash for $name {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.0.to_bits().hash(state);
+            }
+        }
+
+        impl From<$float> for $name {
+            fn from(value: $float) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$name> for $float {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+float_ord!(F32Ord, f32);
+float_ord!(F64Ord, f64);
+
+/// A comparison of orders, for ordering a [`Map`](crate::Map) by something other than the [`Ord`]
+/// implementation of the order type. Used through [`ComparatorMap`](crate::ComparatorMap) and
+/// [`CompareBy`].
+///
+/// Comparators are stateless: a map never stores one, and creates it with [`Default`] whenever it
+/// compares two orders, so they are usually unit structs.
+pub trait Compare<O: ?Sized> {
+    /// Compares two orders.
+    fn compare(&self, a: &O, b: &O) -> Ordering;
+}
+
+/// An order compared by the comparator `C` instead of its own [`Ord`] implementation.
+///
+/// The comparator is only a type parameter, so the wrapper has the same layout as the order and
+/// a reference to an order can be used as a reference to the wrapper with
+/// [`CompareBy::from_ref`].
+#[repr(transparent)]
+pub struct CompareBy<O, C> {
+    pub order: O,
+    comparator: PhantomData<fn() -> C>,
+}
+
+impl<O, C> CompareBy<O, C> {
+    /// Wraps an order.
+    pub const fn new(order: O) -> Self {
+        Self {
+            order,
+            comparator: PhantomData,
+        }
+    }
+
+    /// Wraps a reference to an order, for looking it up without cloning it.
+    pub fn from_ref(order: &O) -> &Self {
+        // SAFETY: `CompareBy` is `repr(transparent)` over `O`, its other field being a zero-sized
+        // `PhantomData`.
+        unsafe { &*(order as *const O).cast::<Self>() }
+    }
+
+    /// Unwraps the order.
+    pub fn into_inner(self) -> O {
+        self.order
+    }
+}
+
+impl<O, C> From<O> for CompareBy<O, C> {
+    fn from(order: O) -> Self {
+        Self::new(order)
+    }
+}
+
+impl<O: Clone, C> Clone for CompareBy<O, C> {
+    fn clone(&self) -> Self {
+        Self::new(self.order.clone())
+    }
+}
+
+impl<O: Copy, C> Copy for CompareBy<O, C> {}
+
+impl<O: Debug, C> Debug for CompareBy<O, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.order.fmt(f)
+    }
+}
+
+impl<O, C: Compare<O> + Default> PartialEq for CompareBy<O, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl<O, C: Compare<O> + Default> Eq for CompareBy<O, C> {}
+
+impl<O, C: Compare<O> + Default> PartialOrd for CompareBy<O, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<O, C: Compare<O> + Default> Ord for CompareBy<O, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        C::default().compare(&self.order, &other.order)
+    }
+}
@@ -0,0 +1,28 @@
+/// How to add an entry whose key already has an entry in a [`Map`](crate::Map).
+///
+/// Accepted by [`Map::add_with_policy`](crate::Map::add_with_policy) and the bulk methods built
+/// on it.
+pub enum DuplicatePolicy<'a, K, O, V> {
+    /// Replaces the existing entry.
+    Overwrite,
+    /// Keeps the existing entry, discarding the new one.
+    KeepExisting,
+    /// Keeps the existing entry and returns the new one in an error.
+    Error,
+    /// Replaces the existing entry with the result of the function, which receives the key, the
+    /// existing entry and the new one.
+    #[allow(clippy::type_complexity)]
+    Merge(&'a mut dyn FnMut(&K, (O, V), (O, V)) -> (O, V)),
+}
+
+impl<K, O, V> DuplicatePolicy<'_, K, O, V> {
+    /// Returns the same policy borrowing the merge function, so it can be applied repeatedly.
+    pub(crate) fn reborrow(&mut self) -> DuplicatePolicy<'_, K, O, V> {
+        match self {
+            DuplicatePolicy::Overwrite => DuplicatePolicy::Overwrite,
+            DuplicatePolicy::KeepExisting => DuplicatePolicy::KeepExisting,
+            DuplicatePolicy::Error => DuplicatePolicy::Error,
+            DuplicatePolicy::Merge(f) => DuplicatePolicy::Merge(&mut **f),
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! [proptest](https://docs.rs/proptest) strategies generating [`Map`]s.
+
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use proptest::collection::{vec, SizeRange};
+use proptest::sample::Index;
+use proptest::strategy::Strategy;
+
+use crate::Map;
+
+/// Generates maps from key, order and value strategies. The number of entries is taken from
+/// `size`, but may be lower when generated keys collide.
+pub fn map<K, O, V>(
+    key: K,
+    order: O,
+    value: V,
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = Map<K::Value, O::Value, V::Value>>
+where
+    K: Strategy,
+    O: Strategy,
+    V: Strategy,
+    K::Value: Clone + Eq + Hash,
+    O::Value: Clone + Ord,
+{
+    vec((key, order, value), size).prop_map(Map::from_iter)
+}
+
+/// Like [`map`], but the orders are drawn from a pool of `orders` generated orders, so lower
+/// pool sizes produce more entries sharing an order.
+///
+/// Panics if the pool size range allows zero orders.
+pub fn map_with_order_pool<K, O, V>(
+    key: K,
+    order: O,
+    value: V,
+    size: impl Into<SizeRange>,
+    orders: impl Into<SizeRange>,
+) -> impl Strategy<Value = Map<K::Value, O::Value, V::Value>>
+where
+    K: Strategy,
+    O: Strategy,
+    V: Strategy,
+    K::Value: Clone + Eq + Hash,
+    O::Value: Clone + Ord + Debug,
+{
+    let orders = orders.into();
+    assert!(orders.start() > 0, "the order pool can not be empty");
+    (
+        vec(order, orders),
+        vec((key, proptest::arbitrary::any::<Index>(), value), size),
+    )
+        .prop_map(|(orders, entries)| {
+            entries
+                .into_iter()
+                .map(|(key, index, value)| (key, index.get(&orders).clone(), value))
+                .collect()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn sizes(map in map(any::<u16>(), any::<u8>(), any::<u8>(), 0..50)) {
+            prop_assert!(map.len() < 50);
+            prop_assert_eq!(map.iter().count(), map.len());
+        }
+
+        #[test]
+        fn order_pool(
+            map in map_with_order_pool(0..1000u32, any::<u64>(), any::<()>(), 20..30, 1..=3)
+        ) {
+            prop_assert!(!map.is_empty());
+            prop_assert!(map.orders().len() <= 3);
+        }
+    }
+}
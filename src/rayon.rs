@@ -0,0 +1,116 @@
+//! Parallel iterators from [rayon](https://docs.rs/rayon). They walk the value map, so the
+//! entries are yielded in arbitrary order.
+
+use std::hash::{BuildHasher, Hash};
+
+use hashbrown::hash_map::rayon::{IntoParIter, ParIter, ParIterMut};
+use rayon::iter::{
+    IntoParallelIterator, IntoParallelRefIterator, IntoParallelRefMutIterator, ParallelExtend,
+    ParallelIterator,
+};
+
+use crate::{KeySet, Map};
+
+type IntoEntry<K, O, V> = fn((K, (O, V))) -> (K, O, V);
+
+type RefEntry<'a, K, O, V> = fn((&'a K, &'a (O, V))) -> (&'a K, &'a O, &'a V);
+
+type MutEntry<'a, K, O, V> = fn((&'a K, &'a mut (O, V))) -> (&'a K, &'a O, &'a mut V);
+
+impl<K: Send + Eq + Hash, O: Send, V: Send, S: BuildHasher, G> IntoParallelIterator
+    for Map<K, O, V, S, G>
+{
+    type Iter = rayon::iter::Map<IntoParIter<K, (O, V)>, IntoEntry<K, O, V>>;
+    type Item = (K, O, V);
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.values
+            .into_par_iter()
+            .map(|(key, (order, value))| (key, order, value))
+    }
+}
+
+impl<'a, K: Sync + Eq + Hash, O: Sync, V: Sync, S: BuildHasher, G> IntoParallelIterator
+    for &'a Map<K, O, V, S, G>
+{
+    type Iter = rayon::iter::Map<ParIter<'a, K, (O, V)>, RefEntry<'a, K, O, V>>;
+    type Item = (&'a K, &'a O, &'a V);
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.values
+            .par_iter()
+            .map(|(key, (order, value))| (key, order, value))
+    }
+}
+
+impl<'a, K: Sync + Eq + Hash, O: Send + Sync, V: Send, S: BuildHasher, G> IntoParallelIterator
+    for &'a mut Map<K, O, V, S, G>
+{
+    type Iter = rayon::iter::Map<ParIterMut<'a, K, (O, V)>, MutEntry<'a, K, O, V>>;
+    type Item = (&'a K, &'a O, &'a mut V);
+
+    fn into_par_iter(self) -> Self::Iter {
+        self.values
+            .par_iter_mut()
+            .map(|(key, (order, value))| (key, &*order, value))
+    }
+}
+
+impl<K, O, V, S, G: KeySet<K, S>> ParallelExtend<(K, O, V)> for Map<K, O, V, S, G>
+where
+    K: Send + Clone + Eq + Hash,
+    O: Send + Clone + Ord,
+    V: Send,
+    S: BuildHasher + Clone,
+{
+    /// Collects the entries in parallel, then adds them, overwriting the entries with the same
+    /// keys. Which of the duplicate keys in `par_iter` wins is unspecified.
+    fn par_extend<I: IntoParallelIterator<Item = (K, O, V)>>(&mut self, par_iter: I) {
+        let entries: Vec<(K, O, V)> = par_iter.into_par_iter().collect();
+        self.extend(entries);
+    }
+}
+
+impl<K: Sync + Eq + Hash, O: Sync, V: Sync, S: BuildHasher, G> Map<K, O, V, S, G> {
+    /// Returns a parallel iterator over the entries in arbitrary order.
+    pub fn par_iter(&self) -> <&Self as IntoParallelIterator>::Iter {
+        self.into_par_iter()
+    }
+}
+
+impl<K: Sync + Eq + Hash, O: Send + Sync, V: Send, S: BuildHasher, G> Map<K, O, V, S, G> {
+    /// Returns a parallel iterator over the entries with mutable references to the values in
+    /// arbitrary order.
+    pub fn par_iter_mut(&mut self) -> <&mut Self as IntoParallelIterator>::Iter {
+        self.into_par_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::iter::{IntoParallelIterator, ParallelExtend, ParallelIterator};
+
+    use crate::Map;
+
+    #[test]
+    fn parallel_iteration() {
+        let mut map: Map<u32, u32, u64> = (0..1000).map(|i| (i, i % 10, 0)).collect();
+        map.par_iter_mut()
+            .for_each(|(key, order, value)| *value = u64::from(key * order));
+        let sum: u64 = map.par_iter().map(|(_key, _order, value)| *value).sum();
+        assert_eq!(sum, (0..1000u64).map(|i| i * (i % 10)).sum::<u64>());
+
+        let mut other = Map::new();
+        other.par_extend(
+            map.clone()
+                .into_par_iter()
+                .filter(|(key, _, _)| key % 2 == 0),
+        );
+        assert_eq!(other.len(), 500);
+        assert_eq!(
+            other.orders().copied().collect::<Vec<_>>(),
+            vec![0, 2, 4, 6, 8]
+        );
+        assert_eq!(other.get(&42), Some((&2, &84)));
+    }
+}
@@ -0,0 +1,222 @@
+//! rkyv support.
+//!
+//! [`Map`] archives as [`ArchivedMap`], a list of entries sorted by order which supports ordered
+//! reads without deserialization.
+
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+use core::ops::{Bound, RangeBounds};
+
+use ::rkyv::collections::util::{Entry, EntryAdapter};
+use ::rkyv::rancor::Fallible;
+use ::rkyv::ser::{Allocator, Writer};
+use ::rkyv::vec::{ArchivedVec, VecResolver};
+use ::rkyv::{Archive, Deserialize, Place, Portable, Serialize};
+
+use crate::{KeySet, Map};
+
+/// An archived [`Map`]. The entries are stored ascending by order, so the ordered reads are
+/// answered by binary search, assuming that archived orders compare like the original ones. Key
+/// lookups scan all entries.
+#[derive(Portable, ::rkyv::bytecheck::CheckBytes)]
+#[bytecheck(crate = ::rkyv::bytecheck)]
+#[rkyv(crate = ::rkyv)]
+#[repr(transparent)]
+pub struct ArchivedMap<K, O, V> {
+    entries: ArchivedVec<Entry<K, Entry<O, V>>>,
+}
+
+fn entry<K, O, V>(entry: &Entry<K, Entry<O, V>>) -> (&K, &O, &V) {
+    (&entry.key, &entry.value.key, &entry.value.value)
+}
+
+impl<K, O, V> ArchivedMap<K, O, V> {
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns an iterator over the entries, ascending by order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&K, &O, &V)> + ExactSizeIterator {
+        self.entries.iter().map(entry)
+    }
+
+    /// Returns references to the order and the value of an entry. Takes O(n) time.
+    pub fn get(&self, key: &K) -> Option<(&O, &V)>
+    where
+        K: PartialEq,
+    {
+        let (_key, order, value) = self.iter().find(|(k, _order, _value)| *k == key)?;
+        Some((order, value))
+    }
+
+    /// Returns an iterator over the entries with orders in the range, ascending by order.
+    pub fn range<R: RangeBounds<O>>(
+        &self,
+        range: R,
+    ) -> impl DoubleEndedIterator<Item = (&K, &O, &V)> + ExactSizeIterator
+    where
+        O: Ord,
+    {
+        let entries = self.entries.as_slice();
+        let start = entries.partition_point(|entry| match range.start_bound() {
+            Bound::Included(start) => entry.value.key < *start,
+            Bound::Excluded(start) => entry.value.key <= *start,
+            Bound::Unbounded => false,
+        });
+        let end = entries.partition_point(|entry| match range.end_bound() {
+            Bound::Included(end) => entry.value.key <= *end,
+            Bound::Excluded(end) => entry.value.key < *end,
+            Bound::Unbounded => true,
+        });
+        entries[start..end.max(start)].iter().map(entry)
+    }
+
+    fn group<'a>(&'a self, order: &'a O) -> (&'a O, Vec<(&'a K, &'a V)>)
+    where
+        O: Ord,
+    {
+        let group = self
+            .range(order..=order)
+            .map(|(key, _order, value)| (key, value))
+            .collect();
+        (order, group)
+    }
+
+    /// Returns references to entries with the smallest order value. The references are unordered.
+    pub fn peek_smallest(&self) -> Option<(&O, Vec<(&K, &V)>)>
+    where
+        O: Ord,
+    {
+        let (_key, order, _value) = self.iter().next()?;
+        Some(self.group(order))
+    }
+
+    /// Returns references to entries with the largest order value. The references are unordered.
+    pub fn peek_largest(&self) -> Option<(&O, Vec<(&K, &V)>)>
+    where
+        O: Ord,
+    {
+        let (_key, order, _value) = self.iter().next_back()?;
+        Some(self.group(order))
+    }
+}
+
+impl<K: Archive, O: Archive, V: Archive, H, G> Archive for Map<K, O, V, H, G> {
+    type Archived = ArchivedMap<K::Archived, O::Archived, V::Archived>;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        // SAFETY: `ArchivedMap` is a `repr(transparent)` wrapper around its entries.
+        let entries = unsafe { out.cast_unchecked() };
+        ArchivedVec::<Entry<K::Archived, Entry<O::Archived, V::Archived>>>::resolve_from_len(
+            self.len(),
+            resolver,
+            entries,
+        );
+    }
+}
+
+impl<K, O, V, H, S, G: KeySet<K, H>> Serialize<S> for Map<K, O, V, H, G>
+where
+    K: Serialize<S> + Eq + Hash,
+    O: Serialize<S>,
+    V: Serialize<S>,
+    H: BuildHasher,
+    S: Fallible + Allocator + Writer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::<Entry<K::Archived, Entry<O::Archived, V::Archived>>>::serialize_from_iter::<
+            EntryAdapter<&K, EntryAdapter<&O, &V, O, V>, K, EntryAdapter<&O, &V, O, V>>,
+            _,
+            _,
+        >(
+            self.iter()
+                .map(|(key, order, value)| EntryAdapter::new(key, EntryAdapter::new(order, value))),
+            serializer,
+        )
+    }
+}
+
+impl<K, O, V, H, D, G: KeySet<K, H>> Deserialize<Map<K, O, V, H, G>, D>
+    for ArchivedMap<K::Archived, O::Archived, V::Archived>
+where
+    K: Archive + Clone + Eq + Hash,
+    O: Archive + Clone + Ord,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    O::Archived: Deserialize<O, D>,
+    V::Archived: Deserialize<V, D>,
+    H: BuildHasher + Clone + Default,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<Map<K, O, V, H, G>, D::Error> {
+        let mut map = Map::default();
+        map.values.reserve(self.len());
+        for (key, order, value) in self.iter() {
+            map.add(
+                key.deserialize(deserializer)?,
+                order.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(map)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::primitive::ArchivedU32;
+    use rkyv::rancor::Error;
+
+    use super::ArchivedMap;
+    use crate::Map;
+
+    #[test]
+    fn ordered_reads() {
+        let mut map = Map::new();
+        for i in 0..10u32 {
+            map.add(i, i % 4, i * 2);
+        }
+        let bytes = rkyv::to_bytes::<Error>(&map).unwrap();
+        let archived =
+            rkyv::access::<ArchivedMap<ArchivedU32, ArchivedU32, ArchivedU32>, Error>(&bytes)
+                .unwrap();
+
+        assert_eq!(archived.len(), 10);
+        let orders: Vec<u32> = archived
+            .iter()
+            .map(|(_key, order, _value)| order.to_native())
+            .collect();
+        assert_eq!(orders, vec![0, 0, 0, 1, 1, 1, 2, 2, 3, 3]);
+        assert_eq!(
+            archived
+                .get(&3.into())
+                .map(|(order, value)| (order.to_native(), value.to_native())),
+            Some((3, 6))
+        );
+        assert_eq!(archived.get(&ArchivedU32::from_native(10)), None);
+
+        let (order, group) = archived.peek_smallest().unwrap();
+        assert_eq!(*order, 0);
+        let mut keys: Vec<u32> = group.iter().map(|(key, _value)| key.to_native()).collect();
+        keys.sort();
+        assert_eq!(keys, vec![0, 4, 8]);
+        let (order, group) = archived.peek_largest().unwrap();
+        assert_eq!((order.to_native(), group.len()), (3, 2));
+
+        let order = ArchivedU32::from_native;
+        assert_eq!(archived.range(order(1)..order(3)).len(), 5);
+        assert_eq!(archived.range(order(2)..).len(), 4);
+        assert_eq!(archived.range(..=order(0)).len(), 3);
+        assert_eq!(archived.range(order(4)..).len(), 0);
+
+        let deserialized = rkyv::deserialize::<Map<u32, u32, u32>, Error>(archived).unwrap();
+        assert_eq!(deserialized, map);
+    }
+}
@@ -0,0 +1,69 @@
+use std::borrow::Cow;
+
+use schemars::{json_schema, JsonSchema, Schema, SchemaGenerator};
+
+use crate::Map;
+
+impl<K: JsonSchema, O: JsonSchema, V: JsonSchema, S, G> JsonSchema for Map<K, O, V, S, G> {
+    fn inline_schema() -> bool {
+        true
+    }
+
+    fn schema_name() -> Cow<'static, str> {
+        format!(
+            "OrdMap_of_{}_and_{}_and_{}",
+            K::schema_name(),
+            O::schema_name(),
+            V::schema_name()
+        )
+        .into()
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        format!(
+            "ordmap::Map<{},{},{}>",
+            K::schema_id(),
+            O::schema_id(),
+            V::schema_id()
+        )
+        .into()
+    }
+
+    /// Describes the serde representation: an array of `[key, order, value]` tuples.
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "array",
+            "items": generator.subschema_for::<(K, O, V)>(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schemars::{json_schema, schema_for};
+
+    use crate::Map;
+
+    #[test]
+    fn schema() {
+        let schema = schema_for!(Map<String, u32, bool>);
+        assert_eq!(
+            schema,
+            json_schema!({
+                "$schema": "https://json-schema.org/draft/2020-12/schema",
+                "title": "OrdMap_of_string_and_uint32_and_boolean",
+                "type": "array",
+                "items": {
+                    "type": "array",
+                    "prefixItems": [
+                        { "type": "string" },
+                        { "type": "integer", "format": "uint32", "minimum": 0 },
+                        { "type": "boolean" }
+                    ],
+                    "minItems": 3,
+                    "maxItems": 3
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,353 @@
+//! Serde support.
+//!
+//! [`Map`] serializes as a sequence of `(key, order, value)` tuples ascending by order, and
+//! rejects duplicate keys when deserializing. The modules here provide other representations and
+//! duplicate handling for use with `#[serde(with = "...")]`.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::{BuildHasher, Hash};
+use core::marker::PhantomData;
+use core::mem;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::{KeySet, Map};
+
+/// The most memory preallocated from a size hint, which comes from untrusted input.
+const MAX_PREALLOCATED_BYTES: usize = 1024 * 1024;
+
+/// Caps a size hint so that preallocating `T`s for it takes at most
+/// [`MAX_PREALLOCATED_BYTES`].
+fn cautious<T>(hint: Option<usize>) -> usize {
+    hint.unwrap_or(0)
+        .min(MAX_PREALLOCATED_BYTES / mem::size_of::<T>().max(1))
+}
+
+/// Adds an entry, failing if an entry with the same key was already deserialized.
+fn add_unique<K, O, V, H, G, E>(
+    map: &mut Map<K, O, V, H, G>,
+    key: K,
+    order: O,
+    value: V,
+) -> Result<(), E>
+where
+    K: Clone + Eq + Hash,
+    O: Clone + Ord,
+    H: BuildHasher + Clone,
+    G: KeySet<K, H>,
+    E: de::Error,
+{
+    if map.contains_key(&key) {
+        return Err(E::custom("duplicate key"));
+    }
+    map.add(key, order, value);
+    Ok(())
+}
+
+impl<K, O, V, H, G: KeySet<K, H>> Serialize for Map<K, O, V, H, G>
+where
+    K: Serialize + Eq + Hash,
+    O: Serialize,
+    V: Serialize,
+    H: BuildHasher,
+{
+    /// Serializes the map as a sequence of `(key, order, value)` tuples ascending by order.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+struct SeqVisitor<K, O, V, H, G> {
+    overwrite: bool,
+    marker: PhantomData<Map<K, O, V, H, G>>,
+}
+
+impl<'de, K, O, V, H, G: KeySet<K, H>> Visitor<'de> for SeqVisitor<K, O, V, H, G>
+where
+    K: Deserialize<'de> + Clone + Eq + Hash,
+    O: Deserialize<'de> + Clone + Ord,
+    V: Deserialize<'de>,
+    H: BuildHasher + Clone + Default,
+{
+    type Value = Map<K, O, V, H, G>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of (key, order, value) tuples")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut map = Map::default();
+        map.values.reserve(cautious::<(K, (O, V))>(seq.size_hint()));
+        while let Some((key, order, value)) = seq.next_element()? {
+            if self.overwrite {
+                map.add(key, order, value);
+            } else {
+                add_unique(&mut map, key, order, value)?;
+            }
+        }
+        Ok(map)
+    }
+}
+
+impl<'de, K, O, V, H, G: KeySet<K, H>> Deserialize<'de> for Map<K, O, V, H, G>
+where
+    K: Deserialize<'de> + Clone + Eq + Hash,
+    O: Deserialize<'de> + Clone + Ord,
+    V: Deserialize<'de>,
+    H: BuildHasher + Clone + Default,
+{
+    /// Deserializes the map from a sequence of `(key, order, value)` tuples. Fails on duplicate
+    /// keys.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(SeqVisitor {
+            overwrite: false,
+            marker: PhantomData,
+        })
+    }
+}
+
+/// The default sequence of `(key, order, value)` tuples, where later tuples overwrite earlier
+/// ones with the same keys when deserializing.
+pub mod overwrite {
+    use super::*;
+
+    pub fn serialize<K, O, V, H, G, S>(
+        map: &Map<K, O, V, H, G>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize + Eq + Hash,
+        O: Serialize,
+        V: Serialize,
+        H: BuildHasher,
+        G: KeySet<K, H>,
+        S: Serializer,
+    {
+        map.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, K, O, V, H, G, D>(
+        deserializer: D,
+    ) -> Result<Map<K, O, V, H, G>, D::Error>
+    where
+        K: Deserialize<'de> + Clone + Eq + Hash,
+        O: Deserialize<'de> + Clone + Ord,
+        V: Deserialize<'de>,
+        H: BuildHasher + Clone + Default,
+        G: KeySet<K, H>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(SeqVisitor {
+            overwrite: true,
+            marker: PhantomData,
+        })
+    }
+}
+
+/// A map from orders to maps from keys to values, ascending by order. Duplicate keys are
+/// rejected when deserializing.
+pub mod by_order {
+    use serde::ser::SerializeMap;
+
+    use super::*;
+
+    struct Group<'a, K, O, V, H, G> {
+        keys: &'a G,
+        map: &'a Map<K, O, V, H, G>,
+    }
+
+    impl<K: Serialize + Eq + Hash, O, V: Serialize, H: BuildHasher, G: KeySet<K, H>> Serialize
+        for Group<'_, K, O, V, H, G>
+    {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut group = serializer.serialize_map(Some(self.keys.len()))?;
+            for key in self.keys.iter() {
+                group.serialize_entry(key, &self.map[key])?;
+            }
+            group.end()
+        }
+    }
+
+    pub fn serialize<K, O, V, H, G, S>(
+        map: &Map<K, O, V, H, G>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize + Eq + Hash,
+        O: Serialize,
+        V: Serialize,
+        H: BuildHasher,
+        G: KeySet<K, H>,
+        S: Serializer,
+    {
+        serializer.collect_map(
+            map.ordered_keys
+                .iter()
+                .map(|(order, keys)| (order, Group { keys, map })),
+        )
+    }
+
+    struct GroupEntries<K, V>(Vec<(K, V)>);
+
+    impl<'de, K: Deserialize<'de>, V: Deserialize<'de>> Deserialize<'de> for GroupEntries<K, V> {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct GroupVisitor<K, V>(PhantomData<(K, V)>);
+
+            impl<'de, K: Deserialize<'de>, V: Deserialize<'de>> Visitor<'de> for GroupVisitor<K, V> {
+                type Value = GroupEntries<K, V>;
+
+                fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("a map from keys to values")
+                }
+
+                fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                    let mut entries = Vec::with_capacity(cautious::<(K, V)>(map.size_hint()));
+                    while let Some(entry) = map.next_entry()? {
+                        entries.push(entry);
+                    }
+                    Ok(GroupEntries(entries))
+                }
+            }
+
+            deserializer.deserialize_map(GroupVisitor(PhantomData))
+        }
+    }
+
+    struct ByOrderVisitor<K, O, V, H, G>(PhantomData<Map<K, O, V, H, G>>);
+
+    impl<'de, K, O, V, H, G> Visitor<'de> for ByOrderVisitor<K, O, V, H, G>
+    where
+        K: Deserialize<'de> + Clone + Eq + Hash,
+        O: Deserialize<'de> + Clone + Ord,
+        V: Deserialize<'de>,
+        H: BuildHasher + Clone + Default,
+        G: KeySet<K, H>,
+    {
+        type Value = Map<K, O, V, H, G>;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a map from orders to maps from keys to values")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut groups: A) -> Result<Self::Value, A::Error> {
+            let mut map = Map::default();
+            while let Some((order, GroupEntries::<K, V>(entries))) = groups.next_entry::<O, _>()? {
+                for (key, value) in entries {
+                    add_unique(&mut map, key, order.clone(), value)?;
+                }
+            }
+            Ok(map)
+        }
+    }
+
+    pub fn deserialize<'de, K, O, V, H, G, D>(
+        deserializer: D,
+    ) -> Result<Map<K, O, V, H, G>, D::Error>
+    where
+        K: Deserialize<'de> + Clone + Eq + Hash,
+        O: Deserialize<'de> + Clone + Ord,
+        V: Deserialize<'de>,
+        H: BuildHasher + Clone + Default,
+        G: KeySet<K, H>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(ByOrderVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::SeqVisitor;
+    use crate::Map;
+
+    #[test]
+    fn round_trip() {
+        let map = Map::from([(1, 20, "a".to_owned()), (2, 10, "b".to_owned())]);
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(json, r#"[[2,10,"b"],[1,20,"a"]]"#);
+        assert_eq!(
+            serde_json::from_str::<Map<i32, i32, String>>(&json).unwrap(),
+            map
+        );
+        assert!(serde_json::from_str::<Map<i32, i32, String>>(r#"{"a":1}"#).is_err());
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let json = r#"[[1,10,"a"],[1,30,"b"],[2,20,"c"]]"#;
+        let error = serde_json::from_str::<Map<i32, i32, String>>(json).unwrap_err();
+        assert!(error.to_string().starts_with("duplicate key"));
+
+        #[derive(Deserialize)]
+        struct Overwriting {
+            #[serde(with = "crate::serde::overwrite")]
+            map: Map<i32, i32, String>,
+        }
+
+        let json = format!(r#"{{"map":{json}}}"#);
+        let overwriting: Overwriting = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            overwriting.map,
+            Map::from([(1, 30, "b".to_owned()), (2, 20, "c".to_owned())])
+        );
+    }
+
+    #[test]
+    fn by_order() {
+        #[derive(Serialize, Deserialize)]
+        struct ByOrder {
+            #[serde(with = "crate::serde::by_order")]
+            map: Map<i32, i32, String>,
+        }
+
+        let by_order = ByOrder {
+            map: Map::from([(1, 20, "a".to_owned()), (2, 10, "b".to_owned())]),
+        };
+        let json = serde_json::to_string(&by_order).unwrap();
+        assert_eq!(json, r#"{"map":{"10":{"2":"b"},"20":{"1":"a"}}}"#);
+        assert_eq!(
+            serde_json::from_str::<ByOrder>(&json).unwrap().map,
+            by_order.map
+        );
+
+        let json = r#"{"map":{"10":{"1":"a"},"20":{"1":"b"}}}"#;
+        assert!(serde_json::from_str::<ByOrder>(json).is_err());
+    }
+
+    #[test]
+    fn untrusted_size_hint() {
+        use serde::de::value::{Error, SeqDeserializer};
+        use serde::de::{DeserializeSeed, SeqAccess, Visitor};
+
+        struct LyingSeq(Option<Vec<i32>>);
+
+        impl<'de> SeqAccess<'de> for LyingSeq {
+            type Error = Error;
+
+            fn next_element_seed<T: DeserializeSeed<'de>>(
+                &mut self,
+                seed: T,
+            ) -> Result<Option<T::Value>, Error> {
+                self.0
+                    .take()
+                    .map(|entry| seed.deserialize(SeqDeserializer::new(entry.into_iter())))
+                    .transpose()
+            }
+
+            fn size_hint(&self) -> Option<usize> {
+                Some(usize::MAX)
+            }
+        }
+
+        let visitor = SeqVisitor {
+            overwrite: false,
+            marker: core::marker::PhantomData,
+        };
+        let map: Map<i32, i32, i32> = visitor.visit_seq(LyingSeq(Some(vec![1, 10, 2]))).unwrap();
+        assert_eq!(map, Map::from([(1, 10, 2)]));
+    }
+}
@@ -0,0 +1,46 @@
+use alloc::collections::BTreeSet;
+
+use crate::{FifoSet, HashSet, LifoSet};
+
+/// A policy for ordering entries with equal orders, chosen when the map is created with
+/// [`MapBuilder::tie_break`](crate::MapBuilder::tie_break).
+///
+/// A policy names the [`KeySet`](crate::KeySet) which keeps the keys of each order.
+pub trait TieBreak {
+    /// The container for the keys of an order.
+    type Group<K, S>;
+}
+
+/// Serves entries with equal orders in no particular order, which is the fastest option and the
+/// default.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Unspecified;
+
+impl TieBreak for Unspecified {
+    type Group<K, S> = HashSet<K, S>;
+}
+
+/// Serves entries with equal orders in the order they got their orders, first in first out.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fifo;
+
+impl TieBreak for Fifo {
+    type Group<K, S> = FifoSet<K, S>;
+}
+
+/// Serves entries with equal orders in the reverse of the order they got their orders, last in
+/// first out.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Lifo;
+
+impl TieBreak for Lifo {
+    type Group<K, S> = LifoSet<K, S>;
+}
+
+/// Serves entries with equal orders ascending by their keys.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ByKey;
+
+impl TieBreak for ByKey {
+    type Group<K, S> = BTreeSet<K>;
+}
@@ -0,0 +1,1059 @@
+//! Maps which store their orders wrapped in another order type, such as [`MaxMap`](crate::MaxMap)
+//! and [`ComparatorMap`](crate::ComparatorMap).
+//!
+//! The iterators here are the iterators of [`Map`] with the orders they yield unwrapped.
+
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::Reverse;
+use core::fmt::{self, Debug};
+use core::hash::{BuildHasher, Hash};
+use core::iter::{self, FusedIterator};
+use core::marker::PhantomData;
+use core::ops::{Add, Bound, Index, RangeBounds};
+
+use crate::iter::Extract;
+use crate::{
+    Compare, CompareBy, DefaultHashBuilder, DuplicatePolicy, GroupIter, HasOrder, HashSet,
+    IntoKeys, IntoValues, KeyCollection, KeySet, Keys, Map, OccupiedError, TryReserveError, Values,
+    ValuesMutUnordered,
+};
+
+/// An order type which a [`WrappedMap`] stores in place of the orders `O` it is given, changing
+/// how they compare.
+///
+/// Implemented by [`Reverse`] and [`CompareBy`].
+pub trait OrderWrapper<O>: Ord {
+    /// Wraps an order.
+    fn wrap(order: O) -> Self;
+
+    /// Unwraps the order.
+    fn unwrap(self) -> O;
+
+    /// Returns a reference to the wrapped order.
+    fn get(&self) -> &O;
+
+    /// Returns a mutable reference to the wrapped order.
+    fn get_mut(&mut self) -> &mut O;
+
+    /// Wraps a reference to an order, for looking it up without cloning it.
+    fn from_ref(order: &O) -> &Self;
+
+    /// Wraps the bounds of a range of orders. The bounds are swapped if the wrapper reverses the
+    /// order.
+    fn wrap_bounds<'a>(
+        start: Bound<&'a O>,
+        end: Bound<&'a O>,
+    ) -> (Bound<&'a Self>, Bound<&'a Self>) {
+        (start.map(Self::from_ref), end.map(Self::from_ref))
+    }
+}
+
+impl<O: Ord> OrderWrapper<O> for Reverse<O> {
+    fn wrap(order: O) -> Self {
+        Reverse(order)
+    }
+
+    fn unwrap(self) -> O {
+        self.0
+    }
+
+    fn get(&self) -> &O {
+        &self.0
+    }
+
+    fn get_mut(&mut self) -> &mut O {
+        &mut self.0
+    }
+
+    fn from_ref(order: &O) -> &Self {
+        // SAFETY: `Reverse` is `repr(transparent)` over `O`.
+        unsafe { &*(order as *const O).cast::<Self>() }
+    }
+
+    fn wrap_bounds<'a>(
+        start: Bound<&'a O>,
+        end: Bound<&'a O>,
+    ) -> (Bound<&'a Self>, Bound<&'a Self>) {
+        (end.map(Self::from_ref), start.map(Self::from_ref))
+    }
+}
+
+impl<O, C: Compare<O> + Default> OrderWrapper<O> for CompareBy<O, C> {
+    fn wrap(order: O) -> Self {
+        CompareBy::new(order)
+    }
+
+    fn unwrap(self) -> O {
+        self.order
+    }
+
+    fn get(&self) -> &O {
+        &self.order
+    }
+
+    fn get_mut(&mut self) -> &mut O {
+        &mut self.order
+    }
+
+    fn from_ref(order: &O) -> &Self {
+        CompareBy::from_ref(order)
+    }
+}
+
+type RefEntry<'a, K, O, V, W> = fn((&'a K, &'a W, &'a V)) -> (&'a K, &'a O, &'a V);
+
+type MutEntry<'a, K, O, V, W> = fn((&'a K, &'a W, &'a mut V)) -> (&'a K, &'a O, &'a mut V);
+
+type IntoEntry<K, O, V, W> = fn((K, W, V)) -> (K, O, V);
+
+fn unwrap_order<O, W: OrderWrapper<O>>(order: &W) -> &O {
+    order.get()
+}
+
+fn unwrap_order_len<O, W: OrderWrapper<O>>((order, len): (&W, usize)) -> (&O, usize) {
+    (order.get(), len)
+}
+
+fn unwrap_ref_entry<'a, K, O, V, W: OrderWrapper<O>>(
+    (key, order, value): (&'a K, &'a W, &'a V),
+) -> (&'a K, &'a O, &'a V) {
+    (key, order.get(), value)
+}
+
+fn unwrap_mut_entry<'a, K, O, V, W: OrderWrapper<O>>(
+    (key, order, value): (&'a K, &'a W, &'a mut V),
+) -> (&'a K, &'a O, &'a mut V) {
+    (key, order.get(), value)
+}
+
+fn unwrap_entry<K, O, V, W: OrderWrapper<O>>((key, order, value): (K, W, V)) -> (K, O, V) {
+    (key, order.unwrap(), value)
+}
+
+fn unwrap_group<'a, K, O, V, W: OrderWrapper<O>, S, G: KeySet<K, S>>(
+    (order, group): (&'a W, GroupIter<'a, K, W, V, S, G>),
+) -> (&'a O, GroupIter<'a, K, W, V, S, G>) {
+    (order.get(), group)
+}
+
+fn unwrap_into_group<K, O, V, W: OrderWrapper<O>>(
+    (order, group): (W, Vec<(K, V)>),
+) -> (O, Vec<(K, V)>) {
+    (order.unwrap(), group)
+}
+
+pub(crate) fn unwrap_entries<K, O, V, W: OrderWrapper<O>>(
+    entries: Vec<(K, W, V)>,
+) -> Vec<(K, O, V)> {
+    entries.into_iter().map(unwrap_entry).collect()
+}
+
+fn wrap_entries<K, O, V, W: OrderWrapper<O>, I: IntoIterator<Item = (K, O, V)>>(
+    iter: I,
+) -> impl Iterator<Item = (K, W, V)> {
+    iter.into_iter()
+        .map(|(key, order, value)| (key, W::wrap(order), value))
+}
+
+fn wrap_range<'a, O: 'a, W: OrderWrapper<O>, R: RangeBounds<O>>(
+    range: &'a R,
+) -> (Bound<&'a W>, Bound<&'a W>) {
+    W::wrap_bounds(range.start_bound(), range.end_bound())
+}
+
+/// An iterator over the distinct orders of a [`WrappedMap`], in map order.
+pub type Orders<'a, K, O, W, S = DefaultHashBuilder, G = HashSet<K, S>> =
+    iter::Map<crate::Orders<'a, K, W, S, G>, fn(&'a W) -> &'a O>;
+
+/// An iterator over the distinct orders of a [`WrappedMap`] with the number of entries having
+/// each, in map order.
+pub type OrderDistribution<'a, K, O, W, S = DefaultHashBuilder, G = HashSet<K, S>> =
+    iter::Map<crate::OrderDistribution<'a, K, W, S, G>, fn((&'a W, usize)) -> (&'a O, usize)>;
+
+/// An iterator over the entries of a [`WrappedMap`] in arbitrary order.
+pub type IterUnordered<'a, K, O, V, W> =
+    iter::Map<crate::IterUnordered<'a, K, W, V>, RefEntry<'a, K, O, V, W>>;
+
+/// An iterator over the entries of a [`WrappedMap`] with mutable references to the values, in
+/// arbitrary order.
+pub type IterMutUnordered<'a, K, O, V, W> =
+    iter::Map<crate::IterMutUnordered<'a, K, W, V>, MutEntry<'a, K, O, V, W>>;
+
+/// An iterator over the entries of a [`WrappedMap`], in map order.
+pub type Iter<'a, K, O, V, W, S = DefaultHashBuilder, G = HashSet<K, S>> =
+    iter::Map<crate::Iter<'a, K, W, V, S, G>, RefEntry<'a, K, O, V, W>>;
+
+/// An iterator over the entries of a [`WrappedMap`] with mutable references to the values, in
+/// map order.
+pub type IterMut<'a, K, O, V, W, S = DefaultHashBuilder, G = HashSet<K, S>> =
+    iter::Map<crate::IterMut<'a, K, W, V, S, G>, MutEntry<'a, K, O, V, W>>;
+
+/// An owning iterator over the entries of a [`WrappedMap`], in map order.
+pub type IntoIter<K, O, V, W, S = DefaultHashBuilder, G = HashSet<K, S>> =
+    iter::Map<crate::IntoIter<K, W, V, S, G>, IntoEntry<K, O, V, W>>;
+
+/// An iterator over the order groups of a [`WrappedMap`], in map order.
+#[allow(clippy::type_complexity)]
+pub type Groups<'a, K, O, V, W, S = DefaultHashBuilder, G = HashSet<K, S>> = iter::Map<
+    crate::Groups<'a, K, W, V, S, G>,
+    fn((&'a W, GroupIter<'a, K, W, V, S, G>)) -> (&'a O, GroupIter<'a, K, W, V, S, G>),
+>;
+
+/// An owning iterator over the order groups of a [`WrappedMap`], in map order.
+pub type IntoGroups<K, O, V, W, S = DefaultHashBuilder, G = HashSet<K, S>> =
+    iter::Map<crate::IntoGroups<K, W, V, S, G>, fn((W, Vec<(K, V)>)) -> (O, Vec<(K, V)>)>;
+
+/// A draining iterator over the entries of a [`WrappedMap`], in map order.
+pub type Drain<'a, K, O, V, W, S = DefaultHashBuilder, G = HashSet<K, S>> =
+    iter::Map<crate::Drain<'a, K, W, V, S, G>, IntoEntry<K, O, V, W>>;
+
+/// An iterator over the entries of a [`WrappedMap`] with orders in a range, in map order.
+pub type Range<'a, K, O, V, W, S = DefaultHashBuilder, G = HashSet<K, S>> =
+    iter::Map<crate::Range<'a, K, W, V, S, G>, RefEntry<'a, K, O, V, W>>;
+
+/// An iterator over the entries of a [`WrappedMap`] with orders in a range with mutable
+/// references to the values, in map order.
+pub type RangeMut<'a, K, O, V, W, S = DefaultHashBuilder, G = HashSet<K, S>> =
+    iter::Map<crate::RangeMut<'a, K, W, V, S, G>, MutEntry<'a, K, O, V, W>>;
+
+/// A draining iterator over the entries of a [`WrappedMap`] with orders in a range, in map order.
+pub type DrainRange<'a, K, O, V, W, S = DefaultHashBuilder> =
+    iter::Map<crate::DrainRange<'a, K, W, V, S>, IntoEntry<K, O, V, W>>;
+
+/// An iterator which removes and yields the entries of a [`WrappedMap`] that match a predicate,
+/// in map order. Entries with equal orders are not ordered.
+///
+/// Constructed by [`WrappedMap::extract_if`].
+pub struct ExtractIf<'a, K, O, V, W, F, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    extract: Extract<'a, K, W, V, S, G>,
+    pred: F,
+    marker: PhantomData<fn() -> O>,
+}
+
+impl<K, O, V, W, F, S, G: KeySet<K, S>> Iterator for ExtractIf<'_, K, O, V, W, F, S, G>
+where
+    K: Clone + Eq + Hash,
+    W: OrderWrapper<O> + Clone,
+    F: FnMut(&K, &O, &mut V) -> bool,
+    S: BuildHasher + Clone,
+{
+    type Item = (K, O, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pred = &mut self.pred;
+        let entry = self
+            .extract
+            .next(|key, order, value| pred(key, order.get(), value))?;
+        Some(unwrap_entry(entry))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.extract.map.len()))
+    }
+}
+
+impl<K, O, V, W, F, S, G: KeySet<K, S>> FusedIterator for ExtractIf<'_, K, O, V, W, F, S, G>
+where
+    K: Clone + Eq + Hash,
+    W: OrderWrapper<O> + Clone,
+    F: FnMut(&K, &O, &mut V) -> bool,
+    S: BuildHasher + Clone,
+{
+}
+
+/// A map like [`Map`] which stores its orders wrapped in `W`, which decides how they compare.
+/// This type adds and removes the wrapping at its boundary, and is used through the
+/// [`MaxMap`](crate::MaxMap) and [`ComparatorMap`](crate::ComparatorMap) aliases.
+///
+/// The map order is the order of the wrapped orders: descending for a `MaxMap` and ascending by
+/// the comparator for a `ComparatorMap`. Iteration follows it, while ranges and orders passed to
+/// the methods are written in terms of the orders themselves, so `max_map.range(10..20)` yields
+/// the entries with orders from 19 down to 10. The methods named after the smallest and largest
+/// orders are provided by each alias. The underlying map is available through
+/// [`WrappedMap::as_map`] for the parts of the [`Map`] API taking or returning entries, cursors
+/// and guards.
+pub struct WrappedMap<K, O, V, W, S = DefaultHashBuilder, G = HashSet<K, S>> {
+    pub(crate) map: Map<K, W, V, S, G>,
+    marker: PhantomData<fn() -> O>,
+}
+
+impl<K, O, V, W> WrappedMap<K, O, V, W> {
+    /// Creates an empty map.
+    pub fn new() -> Self {
+        Self::from_map(Map::new())
+    }
+
+    /// Creates an empty map with space for at least `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_map(Map::with_capacity(capacity))
+    }
+}
+
+impl<K, O, V, W, S> WrappedMap<K, O, V, W, S> {
+    /// Creates an empty map which will use the given hasher.
+    pub const fn with_hasher(hasher: S) -> Self {
+        Self::from_map(Map::with_hasher(hasher))
+    }
+
+    /// Creates an empty map with space for at least `capacity` entries which will use the given
+    /// hasher.
+    pub fn with_capacity_and_hasher(capacity: usize, hasher: S) -> Self {
+        Self::from_map(Map::with_capacity_and_hasher(capacity, hasher))
+    }
+}
+
+impl<K, O, V, W, S, G> WrappedMap<K, O, V, W, S, G> {
+    /// Wraps a map with wrapped orders.
+    pub const fn from_map(map: Map<K, W, V, S, G>) -> Self {
+        Self {
+            map,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the underlying map with wrapped orders.
+    pub fn as_map(&self) -> &Map<K, W, V, S, G> {
+        &self.map
+    }
+
+    /// Returns the underlying map with wrapped orders.
+    pub fn as_map_mut(&mut self) -> &mut Map<K, W, V, S, G> {
+        &mut self.map
+    }
+
+    /// Returns the underlying map with wrapped orders.
+    pub fn into_map(self) -> Map<K, W, V, S, G> {
+        self.map
+    }
+
+    /// Returns a reference to the hasher of the map.
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+
+    /// Returns the number of entries in the map.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns `true` if the map contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the number of distinct orders in the map.
+    pub fn group_count(&self) -> usize {
+        self.map.group_count()
+    }
+
+    /// Returns the number of entries the map can hold without reallocating the value map.
+    pub fn capacity(&self) -> usize {
+        self.map.capacity()
+    }
+
+    /// Returns an iterator over mutable references to the values in arbitrary order.
+    pub fn values_mut_unordered(&mut self) -> ValuesMutUnordered<'_, K, W, V> {
+        self.map.values_mut_unordered()
+    }
+
+    /// Removes all entries, keeping the allocated memory for reuse.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+}
+
+impl<K, O, V, W: OrderWrapper<O>, S, G> WrappedMap<K, O, V, W, S, G> {
+    /// Returns an iterator over the distinct orders, in map order.
+    pub fn orders(&self) -> Orders<'_, K, O, W, S, G> {
+        self.map.orders().map(unwrap_order)
+    }
+
+    /// Returns an iterator over the keys, in map order.
+    pub fn keys(&self) -> Keys<'_, K, W, S, G>
+    where
+        G: KeySet<K, S>,
+    {
+        self.map.keys()
+    }
+
+    /// Returns an iterator over the distinct orders with the number of entries having each, in
+    /// map order.
+    pub fn order_distribution(&self) -> OrderDistribution<'_, K, O, W, S, G>
+    where
+        G: KeySet<K, S>,
+    {
+        self.map.order_distribution().map(unwrap_order_len)
+    }
+
+    /// Returns the order at the `p`-th percentile of the entries in map order using the
+    /// nearest-rank method, so the 0th percentile of a [`MaxMap`](crate::MaxMap) is its largest
+    /// order.
+    ///
+    /// Panics if `p` is not within `0.0..=100.0`.
+    pub fn percentile_order(&self, p: f64) -> Option<&O>
+    where
+        G: KeySet<K, S>,
+    {
+        Some(self.map.percentile_order(p)?.get())
+    }
+
+    /// Returns the median order of the entries, the first one of the two middle orders in map
+    /// order if there is an even number of entries.
+    pub fn median_order(&self) -> Option<&O>
+    where
+        G: KeySet<K, S>,
+    {
+        Some(self.map.median_order()?.get())
+    }
+
+    /// Consumes the map, returning an iterator over the keys in map order.
+    pub fn into_keys(self) -> IntoKeys<K, W, S, G>
+    where
+        G: KeySet<K, S>,
+    {
+        self.map.into_keys()
+    }
+
+    /// Returns an iterator over the entries in arbitrary order.
+    pub fn iter_unordered(&self) -> IterUnordered<'_, K, O, V, W> {
+        self.map.iter_unordered().map(unwrap_ref_entry)
+    }
+
+    /// Returns an iterator over the entries with mutable references to the values in arbitrary
+    /// order.
+    pub fn iter_mut_unordered(&mut self) -> IterMutUnordered<'_, K, O, V, W> {
+        self.map.iter_mut_unordered().map(unwrap_mut_entry)
+    }
+}
+
+impl<K: Eq + Hash, O, V, W: OrderWrapper<O>, S: BuildHasher, G: KeySet<K, S>>
+    WrappedMap<K, O, V, W, S, G>
+{
+    /// Returns an iterator over the entries, in map order. Entries with equal orders are not
+    /// ordered.
+    pub fn iter(&self) -> Iter<'_, K, O, V, W, S, G> {
+        self.into_iter()
+    }
+
+    /// Returns an iterator over the entries with mutable references to the values, in map order.
+    /// Entries with equal orders are not ordered.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, O, V, W, S, G> {
+        self.into_iter()
+    }
+
+    /// Returns an iterator over the values, in map order.
+    pub fn values(&self) -> Values<'_, K, W, V, S, G> {
+        self.map.values()
+    }
+
+    /// Returns an iterator over the order groups, in map order. Each group is yielded as its
+    /// order and an iterator over its keys and values.
+    pub fn groups(&self) -> Groups<'_, K, O, V, W, S, G> {
+        self.map.groups().map(unwrap_group)
+    }
+
+    /// Consumes the map, returning an iterator over the order groups in map order. Items in a
+    /// group are not ordered.
+    pub fn into_groups(self) -> IntoGroups<K, O, V, W, S, G> {
+        self.map.into_groups().map(unwrap_into_group)
+    }
+
+    /// Consumes the map, returning an iterator over the values in map order.
+    pub fn into_values(self) -> IntoValues<K, W, V, S, G> {
+        self.map.into_values()
+    }
+
+    /// Removes all entries, returning them as an iterator in map order. Entries with equal orders
+    /// are not ordered.
+    pub fn drain(&mut self) -> Drain<'_, K, O, V, W, S, G> {
+        self.map.drain().map(unwrap_entry)
+    }
+}
+
+impl<K, O, V, W, S, G> WrappedMap<K, O, V, W, S, G>
+where
+    K: Clone + Eq + Hash,
+    O: Clone,
+    W: OrderWrapper<O> + Clone,
+    S: BuildHasher + Clone,
+    G: KeySet<K, S>,
+{
+    /// Returns `true` if any entry has an order comparing equal to the given one.
+    pub fn contains_order(&self, order: &O) -> bool {
+        self.map.contains_order(W::from_ref(order))
+    }
+
+    /// Returns the number of entries with an order comparing equal to the given one.
+    pub fn group_len(&self, order: &O) -> usize {
+        self.map.group_len(W::from_ref(order))
+    }
+
+    /// Reserves space for at least `additional` more entries in the value map.
+    pub fn reserve(&mut self, additional: usize) {
+        self.map.reserve(additional);
+    }
+
+    /// Tries to reserve space for at least `additional` more entries in the value map, returning
+    /// an error instead of aborting if the allocation fails.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.map.try_reserve(additional)
+    }
+
+    /// Shrinks the value map and every group as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        self.map.shrink_to_fit();
+    }
+
+    /// Returns `true` if the map contains an entry with the given key.
+    pub fn contains_key<Q: Eq + Hash + ?Sized>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Returns references to the order and the value of an entry.
+    pub fn get<Q: Eq + Hash + ?Sized>(&self, key: &Q) -> Option<(&O, &V)>
+    where
+        K: Borrow<Q>,
+    {
+        let (order, value) = self.map.get(key)?;
+        Some((order.get(), value))
+    }
+
+    /// Returns a reference to the order of an entry.
+    pub fn get_order<Q: Eq + Hash + ?Sized>(&self, key: &Q) -> Option<&O>
+    where
+        K: Borrow<Q>,
+    {
+        Some(self.map.get_order(key)?.get())
+    }
+
+    /// Returns a reference to the order and a mutable reference to the value of an entry. The order
+    /// can not be changed this way.
+    pub fn get_mut<Q: Eq + Hash + ?Sized>(&mut self, key: &Q) -> Option<(&O, &mut V)>
+    where
+        K: Borrow<Q>,
+    {
+        let (order, value) = self.map.get_mut(key)?;
+        Some((order.get(), value))
+    }
+
+    /// Returns mutable references to the values of several entries at once, leaving their orders
+    /// untouched. Returns `None` if any key has no entry or if any two keys are equal.
+    pub fn get_many_mut<Q: Eq + Hash + ?Sized, const N: usize>(
+        &mut self,
+        keys: [&Q; N],
+    ) -> Option<[&mut V; N]>
+    where
+        K: Borrow<Q>,
+    {
+        self.map.get_many_mut(keys)
+    }
+
+    /// Like [`WrappedMap::get_many_mut`], but for any number of keys.
+    pub fn get_many_mut_slice<Q: Eq + Hash + ?Sized>(&mut self, keys: &[&Q]) -> Option<Vec<&mut V>>
+    where
+        K: Borrow<Q>,
+    {
+        self.map.get_many_mut_slice(keys)
+    }
+
+    /// Returns a mutable reference to the value of an entry, leaving its order untouched.
+    pub fn get_value_mut<Q: Eq + Hash + ?Sized>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+    {
+        self.map.get_value_mut(key)
+    }
+
+    /// Calls `f` on the value of an entry, leaving its order untouched. Returns `false` if there
+    /// is no entry with the given key.
+    pub fn update_value<Q: Eq + Hash + ?Sized, F: FnOnce(&mut V)>(&mut self, key: &Q, f: F) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map.update_value(key, f)
+    }
+
+    /// Calls `f` on the value of an entry, then moves the entry to the order derived from the
+    /// value if it changed. Returns `false` if there is no entry with the given key.
+    pub fn update_value_auto<Q: Eq + Hash + ?Sized, F: FnOnce(&mut V)>(
+        &mut self,
+        key: &Q,
+        f: F,
+    ) -> bool
+    where
+        K: Borrow<Q>,
+        V: HasOrder<O>,
+    {
+        self.update(key, |order, value| {
+            f(value);
+            *order = value.order();
+        })
+    }
+
+    /// Calls `f` on the order and the value of an entry, moving the entry to its new group if `f`
+    /// changed the order. Returns `false` if there is no entry with the given key.
+    pub fn update<Q: Eq + Hash + ?Sized, F: FnOnce(&mut O, &mut V)>(
+        &mut self,
+        key: &Q,
+        f: F,
+    ) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map
+            .update(key, |order, value| f(order.get_mut(), value))
+    }
+
+    /// Removes an entry by key.
+    pub fn remove<Q: Eq + Hash + ?Sized>(&mut self, key: &Q) -> Option<(O, V)>
+    where
+        K: Borrow<Q>,
+    {
+        let (order, value) = self.map.remove(key)?;
+        Some((order.unwrap(), value))
+    }
+
+    /// Removes the entries with the given keys, returning them in the order of the keys. Keys
+    /// without entries are skipped.
+    pub fn remove_many<'a, Q: Eq + Hash + ?Sized + 'a, I: IntoIterator<Item = &'a Q>>(
+        &mut self,
+        keys: I,
+    ) -> Vec<(K, O, V)>
+    where
+        K: Borrow<Q>,
+    {
+        unwrap_entries(self.map.remove_many(keys))
+    }
+
+    /// Keeps only the entries with keys in `keys`.
+    pub fn retain_keys<C: KeyCollection<K>>(&mut self, keys: &C) {
+        self.map.retain_keys(keys);
+    }
+
+    /// Removes the entries with keys in `keys`.
+    pub fn remove_keys<C: KeyCollection<K>>(&mut self, keys: &C) {
+        self.map.remove_keys(keys);
+    }
+
+    /// Keeps only the entries for which `f` returns `true`. The entries are visited in arbitrary
+    /// order.
+    pub fn retain<F: FnMut(&K, &O, &mut V) -> bool>(&mut self, mut f: F) {
+        self.map
+            .retain(|key, order, value| f(key, order.get(), value));
+    }
+
+    /// Returns an iterator which removes and yields the entries for which `pred` returns `true`,
+    /// in map order. If the iterator is dropped before being exhausted, the remaining entries are
+    /// kept.
+    pub fn extract_if<F: FnMut(&K, &O, &mut V) -> bool>(
+        &mut self,
+        pred: F,
+    ) -> ExtractIf<'_, K, O, V, W, F, S, G> {
+        ExtractIf {
+            extract: Extract::new(&mut self.map),
+            pred,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the entries with orders in the range, in map order.
+    ///
+    /// Panics if the range start is greater than the range end, or if they are equal and both
+    /// excluded.
+    pub fn range<R: RangeBounds<O>>(&self, range: R) -> Range<'_, K, O, V, W, S, G> {
+        self.map.range(wrap_range(&range)).map(unwrap_ref_entry)
+    }
+
+    /// Returns an iterator over the entries with orders in the range with mutable references to
+    /// the values, in map order.
+    ///
+    /// Panics if the range start is greater than the range end, or if they are equal and both
+    /// excluded.
+    pub fn range_mut<R: RangeBounds<O>>(&mut self, range: R) -> RangeMut<'_, K, O, V, W, S, G> {
+        self.map.range_mut(wrap_range(&range)).map(unwrap_mut_entry)
+    }
+
+    /// Returns the number of entries with orders in the range.
+    ///
+    /// Panics if the range start is greater than the range end, or if they are equal and both
+    /// excluded.
+    pub fn count_in_range<R: RangeBounds<O>>(&self, range: R) -> usize {
+        self.map.count_in_range(wrap_range(&range))
+    }
+
+    /// Removes the entries with orders in the range, returning them as an iterator in map order.
+    ///
+    /// Panics if the range start is greater than the range end, or if they are equal and both
+    /// excluded.
+    pub fn drain_range<R: RangeBounds<O>>(&mut self, range: R) -> DrainRange<'_, K, O, V, W, S> {
+        self.map.drain_range(wrap_range(&range)).map(unwrap_entry)
+    }
+
+    /// Splits the map in two at the given order. Returns a map with the entries whose orders come
+    /// at or after `order` in map order, keeping the rest.
+    pub fn split_off(&mut self, order: &O) -> Self {
+        Self::from_map(self.map.split_off(W::from_ref(order)))
+    }
+
+    /// Moves all entries from `other` into `self`, leaving `other` empty. Entries of `other`
+    /// overwrite the entries of `self` with the same keys.
+    pub fn append(&mut self, other: &mut Self) {
+        self.map.append(&mut other.map);
+    }
+
+    /// Moves all entries from `other` into `self`. For keys present in both maps, `f` receives
+    /// the key, the entry of `self` and the entry of `other`, and returns the resulting entry.
+    pub fn merge_with<F: FnMut(&K, (O, V), (O, V)) -> (O, V)>(&mut self, other: Self, mut f: F) {
+        self.map
+            .merge_with(other.map, |key, (old_order, old_value), (order, value)| {
+                let (order, value) = f(
+                    key,
+                    (old_order.unwrap(), old_value),
+                    (order.unwrap(), value),
+                );
+                (W::wrap(order), value)
+            });
+    }
+
+    /// Consumes the map, returning a map with the values replaced by `f` of the key, the order
+    /// and the value.
+    pub fn map_values<V2, F: FnMut(&K, &O, V) -> V2>(
+        self,
+        mut f: F,
+    ) -> WrappedMap<K, O, V2, W, S, G> {
+        WrappedMap::from_map(
+            self.map
+                .map_values(|key, order, value| f(key, order.get(), value)),
+        )
+    }
+
+    /// Consumes the map, returning a map with the keys replaced by `f` of them. If `f` maps
+    /// several keys to the same key, the entry which comes last in map order is kept. The
+    /// returned map has the default groups.
+    pub fn map_keys<K2: Clone + Eq + Hash, F: FnMut(K) -> K2>(
+        self,
+        f: F,
+    ) -> WrappedMap<K2, O, V, W, S> {
+        WrappedMap::from_map(self.map.map_keys(f))
+    }
+
+    /// Consumes the map, returning a map with the orders replaced by their dense ranks in map
+    /// order: the entries served first get 0, those with the next order get 1 and so on.
+    pub fn normalize_orders(self) -> Map<K, usize, V, S, G> {
+        self.map.normalize_orders()
+    }
+
+    /// Returns the number of entries before the group of the entry with the key in map order,
+    /// which is its position in the queue served by the map.
+    pub fn rank<Q: Eq + Hash + ?Sized>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+    {
+        self.map.rank(key)
+    }
+
+    /// Returns an iterator over the keys and values of the entries with an order comparing equal
+    /// to the given one, or `None` if there are none. The entries are not ordered.
+    pub fn keys_with_order(&self, order: &O) -> Option<GroupIter<'_, K, W, V, S, G>> {
+        self.map.keys_with_order(W::from_ref(order))
+    }
+
+    /// Returns the old entry with the same key if there was one.
+    pub fn add(&mut self, key: K, order: O, value: V) -> Option<(O, V)> {
+        let (order, value) = self.map.add(key, W::wrap(order), value)?;
+        Some((order.unwrap(), value))
+    }
+
+    /// Changes the key of an entry, keeping its order and value. Returns `false` without changing
+    /// anything if there is no entry with the old key or there already is one with the new key.
+    pub fn rename_key<Q: Eq + Hash + ?Sized>(&mut self, old: &Q, new: K) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map.rename_key(old, new)
+    }
+
+    /// Moves an entry to a new order, leaving its value untouched. Returns the old order, or
+    /// `None` if there is no entry with the given key.
+    pub fn set_order<Q: Eq + Hash + ?Sized>(&mut self, key: &Q, order: O) -> Option<O>
+    where
+        K: Borrow<Q>,
+    {
+        Some(self.map.set_order(key, W::wrap(order))?.unwrap())
+    }
+
+    /// Moves an entry to its order plus `delta`, leaving its value untouched. Returns the old
+    /// order, or `None` if there is no entry with the given key.
+    pub fn adjust_order<Q: Eq + Hash + ?Sized>(&mut self, key: &Q, delta: O) -> Option<O>
+    where
+        K: Borrow<Q>,
+        O: Add<Output = O>,
+    {
+        let order = self.get_order(key)?.clone() + delta;
+        self.set_order(key, order)
+    }
+
+    /// Exchanges the orders of two entries. Returns `false` without changing anything if either
+    /// key has no entry.
+    pub fn swap_orders<Q: Eq + Hash + ?Sized>(&mut self, a: &Q, b: &Q) -> bool
+    where
+        K: Borrow<Q>,
+    {
+        self.map.swap_orders(a, b)
+    }
+
+    /// Replaces the order of every entry with `f` of it, calling `f` once per group. Groups that
+    /// end up with comparing equal orders are merged.
+    pub fn age<F: FnMut(O) -> O>(&mut self, mut f: F) {
+        self.map.age(|order| W::wrap(f(order.unwrap())));
+    }
+
+    /// Adds `delta` to the order of every entry.
+    pub fn shift_all_orders(&mut self, delta: O)
+    where
+        O: Add<Output = O>,
+    {
+        self.age(|order| order + delta.clone());
+    }
+
+    /// Adds all entries, reserving space for them up front. Returns the entries that were
+    /// replaced by entries with the same keys, in the order they were replaced.
+    pub fn add_batch<I: IntoIterator<Item = (K, O, V)>>(&mut self, iter: I) -> Vec<(K, O, V)> {
+        unwrap_entries(self.map.add_batch(wrap_entries(iter)))
+    }
+
+    /// Adds an entry with the order derived from the value. Returns the old entry with the same
+    /// key if there was one.
+    pub fn add_auto(&mut self, key: K, value: V) -> Option<(O, V)>
+    where
+        V: HasOrder<O>,
+    {
+        let order = value.order();
+        self.add(key, order, value)
+    }
+
+    /// Adds an entry only if there is no entry with the same key. Otherwise returns the entry
+    /// back in the error, leaving the map untouched.
+    pub fn try_add(&mut self, key: K, order: O, value: V) -> Result<(), OccupiedError<K, O, V>> {
+        if self.contains_key(&key) {
+            return Err(OccupiedError { key, order, value });
+        }
+        self.add(key, order, value);
+        Ok(())
+    }
+
+    /// Adds an entry only if there is no entry with the same key. Returns `true` if the entry was
+    /// added.
+    pub fn add_if_absent(&mut self, key: K, order: O, value: V) -> bool {
+        self.try_add(key, order, value).is_ok()
+    }
+
+    /// Adds an entry, resolving a clash with an existing entry with the same key by the policy.
+    /// Returns the entry back in the error if the policy is [`DuplicatePolicy::Error`].
+    pub fn add_with_policy(
+        &mut self,
+        key: K,
+        order: O,
+        value: V,
+        policy: DuplicatePolicy<'_, K, O, V>,
+    ) -> Result<(), OccupiedError<K, O, V>> {
+        if !self.contains_key(&key) {
+            self.add(key, order, value);
+            return Ok(());
+        }
+        match policy {
+            DuplicatePolicy::Overwrite => {
+                self.add(key, order, value);
+            }
+            DuplicatePolicy::KeepExisting => {}
+            DuplicatePolicy::Error => return Err(OccupiedError { key, order, value }),
+            DuplicatePolicy::Merge(f) => {
+                let old_entry = self.remove(&key).unwrap();
+                let (order, value) = f(&key, old_entry, (order, value));
+                self.add(key, order, value);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the entries one by one with [`WrappedMap::add_with_policy`]. Stops at the first
+    /// error, keeping the entries added before it.
+    pub fn extend_with_policy<I: IntoIterator<Item = (K, O, V)>>(
+        &mut self,
+        iter: I,
+        mut policy: DuplicatePolicy<'_, K, O, V>,
+    ) -> Result<(), OccupiedError<K, O, V>> {
+        for (key, order, value) in iter {
+            self.add_with_policy(key, order, value, policy.reborrow())?;
+        }
+        Ok(())
+    }
+
+    /// Collects the entries into a map with [`WrappedMap::extend_with_policy`].
+    pub fn from_iter_with_policy<I: IntoIterator<Item = (K, O, V)>>(
+        iter: I,
+        policy: DuplicatePolicy<'_, K, O, V>,
+    ) -> Result<Self, OccupiedError<K, O, V>>
+    where
+        S: Default,
+    {
+        let mut map = Self::default();
+        map.extend_with_policy(iter, policy)?;
+        Ok(map)
+    }
+
+    /// Adds the order and the value returned by `f` if there is no entry with the key. Returns
+    /// references to the order and the value of the entry either way.
+    pub fn get_or_insert_with<F: FnOnce() -> (O, V)>(&mut self, key: K, f: F) -> (&O, &mut V) {
+        let (order, value) = self.map.get_or_insert_with(key, || {
+            let (order, value) = f();
+            (W::wrap(order), value)
+        });
+        (order.get(), value)
+    }
+
+    /// Moves all entries with an order comparing equal to `from` to the order `to`, merging them
+    /// with the entries already there. Returns the number of entries moved.
+    pub fn reassign_order(&mut self, from: &O, to: O) -> usize {
+        self.map.reassign_order(W::from_ref(from), W::wrap(to))
+    }
+}
+
+impl<K: Eq + Hash, O, V, W: OrderWrapper<O>, S: BuildHasher, G: KeySet<K, S>> IntoIterator
+    for WrappedMap<K, O, V, W, S, G>
+{
+    type Item = (K, O, V);
+    type IntoIter = IntoIter<K, O, V, W, S, G>;
+
+    /// Consumes the map, yielding the entries in map order. Entries with equal orders are not
+    /// ordered.
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.into_iter().map(unwrap_entry)
+    }
+}
+
+impl<'a, K: Eq + Hash, O, V, W: OrderWrapper<O>, S: BuildHasher, G: KeySet<K, S>> IntoIterator
+    for &'a WrappedMap<K, O, V, W, S, G>
+{
+    type Item = (&'a K, &'a O, &'a V);
+    type IntoIter = Iter<'a, K, O, V, W, S, G>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter().map(unwrap_ref_entry)
+    }
+}
+
+impl<'a, K: Eq + Hash, O, V, W: OrderWrapper<O>, S: BuildHasher, G: KeySet<K, S>> IntoIterator
+    for &'a mut WrappedMap<K, O, V, W, S, G>
+{
+    type Item = (&'a K, &'a O, &'a mut V);
+    type IntoIter = IterMut<'a, K, O, V, W, S, G>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter_mut().map(unwrap_mut_entry)
+    }
+}
+
+impl<K, O, V, W, S: Default, G> Default for WrappedMap<K, O, V, W, S, G> {
+    /// Creates an empty map.
+    fn default() -> Self {
+        Self::from_map(Map::default())
+    }
+}
+
+impl<K, O, V, W, S, G> Clone for WrappedMap<K, O, V, W, S, G>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    W: Clone + Ord,
+    S: BuildHasher + Clone,
+    G: KeySet<K, S> + Clone,
+{
+    fn clone(&self) -> Self {
+        Self::from_map(self.map.clone())
+    }
+}
+
+impl<K, O, V, W, S, G> Extend<(K, O, V)> for WrappedMap<K, O, V, W, S, G>
+where
+    K: Clone + Eq + Hash,
+    W: OrderWrapper<O> + Clone,
+    S: BuildHasher + Clone,
+    G: KeySet<K, S>,
+{
+    /// Adds all entries, overwriting the entries with the same keys.
+    fn extend<I: IntoIterator<Item = (K, O, V)>>(&mut self, iter: I) {
+        self.map.extend(wrap_entries(iter));
+    }
+}
+
+impl<K, O, V, W, S, G> FromIterator<(K, O, V)> for WrappedMap<K, O, V, W, S, G>
+where
+    K: Clone + Eq + Hash,
+    W: OrderWrapper<O> + Clone,
+    S: BuildHasher + Clone + Default,
+    G: KeySet<K, S>,
+{
+    /// Collects the entries into a map. Later entries overwrite earlier ones with the same keys.
+    fn from_iter<I: IntoIterator<Item = (K, O, V)>>(iter: I) -> Self {
+        Self::from_map(wrap_entries(iter).collect())
+    }
+}
+
+impl<K, O, V, W, const N: usize> From<[(K, O, V); N]> for WrappedMap<K, O, V, W>
+where
+    K: Clone + Eq + Hash,
+    W: OrderWrapper<O> + Clone,
+{
+    /// Later entries overwrite earlier ones with the same keys.
+    fn from(entries: [(K, O, V); N]) -> Self {
+        Self::from_iter(entries)
+    }
+}
+
+impl<K, Q, O, V, W, S, G> Index<&Q> for WrappedMap<K, O, V, W, S, G>
+where
+    K: Borrow<Q> + Eq + Hash,
+    Q: Eq + Hash + ?Sized,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    /// Returns a reference to the value of an entry.
+    ///
+    /// Panics if there is no entry with the given key.
+    fn index(&self, key: &Q) -> &V {
+        &self.map[key]
+    }
+}
+
+impl<K: Eq + Hash, O, V: PartialEq, W: PartialEq, S: BuildHasher, G> PartialEq
+    for WrappedMap<K, O, V, W, S, G>
+{
+    /// Maps are equal if they associate the same keys with comparing equal orders and equal
+    /// values.
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<K: Eq + Hash, O, V: Eq, W: Eq, S: BuildHasher, G> Eq for WrappedMap<K, O, V, W, S, G> {}
+
+impl<K, O, V, W, S, G> Debug for WrappedMap<K, O, V, W, S, G>
+where
+    K: Debug + Eq + Hash,
+    O: Debug,
+    V: Debug,
+    W: OrderWrapper<O>,
+    S: BuildHasher,
+    G: KeySet<K, S>,
+{
+    /// Formats the entries as a list of `(key, order, value)` tuples in map order.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}